@@ -28,6 +28,9 @@ pub enum Commands {
     
     #[command(alias = "b", about = "Batch process multiple files")]
     Batch(BatchArgs),
+    
+    #[command(about = "Dump an annotated listing of the compressed bitstream")]
+    Dump(DumpArgs),
 }
 
 #[derive(Debug, Args)]
@@ -44,17 +47,56 @@ pub struct CompressArgs {
     #[arg(long, help = "Enable string value pool (v2 format)")]
     pub enable_pool: bool,
     
-    #[arg(long, default_value = "3", help = "Pool minimum repeat count")]
+    #[arg(long, default_value = "3", help = "Pool minimum repeat count (0: decided by bit-cost model only)")]
     pub pool_min_repeats: u32,
     
-    #[arg(long, default_value = "8", help = "Pool minimum string length")]
+    #[arg(long, default_value = "8", help = "Pool minimum string length (0: decided by bit-cost model only)")]
     pub pool_min_string_len: usize,
     
+    #[arg(long = "meta", value_name = "KEY=VALUE", help = "Attach metadata to the header, repeatable (v3 format)")]
+    pub metadata: Vec<String>,
+    
+    #[arg(long, help = "Store repeated objects/arrays once in the value pool (v3 format)")]
+    pub dedup_subtrees: bool,
+    
+    #[arg(long, default_value = "4", help = "Minimum node count of a deduplicated subtree")]
+    pub subtree_min_nodes: usize,
+    
+    #[arg(long, help = "Huffman-code pool reference ids by frequency (v3 format)")]
+    pub huffman_refs: bool,
+    
+    #[arg(long, help = "Per-key value dictionaries for low-cardinality string fields (v3 format)")]
+    pub value_dicts: bool,
+    
+    #[arg(long, default_value = "16", help = "Maximum distinct values of a key with a value dictionary")]
+    pub value_dict_max: usize,
+    
+    #[arg(long, value_enum, help = "Compression preset; overrides the feature switches above (max keeps the smallest candidate)")]
+    pub level: Option<CompressLevel>,
+    
+    #[arg(long, help = "Compact encodings for UUID, hex, base64 and decimal strings (v3 format)")]
+    pub string_subtypes: bool,
+    
+    #[arg(long, help = "Compact delta-coded encoding for RFC 3339 timestamp strings (v3 format)")]
+    pub timestamps: bool,
+    
+    #[arg(long, help = "Block encoding for arrays of only floats or only booleans (v3 format)")]
+    pub typed_arrays: bool,
+    
+    #[arg(long, value_name = "N", conflicts_with = "float_digits", help = "Lossy: round floats to N decimal places and store them as scaled integers (v3 format)")]
+    pub float_decimals: Option<u8>,
+    
+    #[arg(long, value_name = "N", help = "Lossy: round floats to N significant digits and store them as scaled integers (v3 format)")]
+    pub float_digits: Option<u8>,
+    
     #[arg(long, help = "Pretty output (applies to base64 format only)")]
     pub pretty: bool,
     
     #[arg(long, help = "Show compression statistics")]
     pub stats: bool,
+    
+    #[arg(long, help = "Show a per-section and per-value-type size report")]
+    pub report: bool,
 }
 
 #[derive(Debug, Args)]
@@ -73,6 +115,15 @@ pub struct DecompressArgs {
     
     #[arg(long, help = "Compact JSON output")]
     pub compact: bool,
+    
+    #[arg(long, help = "Reject trailing data after the compressed body")]
+    pub strict: bool,
+    
+    #[arg(long, value_enum, default_value = "string", help = "How NaN/Infinity values are written to JSON")]
+    pub non_finite: NonFiniteRepr,
+    
+    #[arg(long, help = "Decode values of unknown extension types as null instead of failing")]
+    pub skip_extensions: bool,
 }
 
 #[derive(Debug, Args)]
@@ -87,6 +138,15 @@ pub struct InfoArgs {
     pub detailed: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct DumpArgs {
+    #[arg(help = "Compressed file path, use '-' for stdin")]
+    pub input: String,
+    
+    #[arg(short, long, value_enum, default_value = "auto", help = "Input format")]
+    pub format: InputFormat,
+}
+
 #[derive(Debug, Args)]
 pub struct BatchArgs {
     #[arg(value_enum, help = "Operation type")]
@@ -119,6 +179,43 @@ pub enum OutputFormat {
     Bytes,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum CompressLevel {
+    #[value(name = "fast")]
+    Fast,
+    #[value(name = "default")]
+    Default,
+    #[value(name = "max")]
+    Max,
+}
+
+impl From<CompressLevel> for json_packer::Level {
+    fn from(level: CompressLevel) -> Self {
+        match level {
+            CompressLevel::Fast => json_packer::Level::Fast,
+            CompressLevel::Default => json_packer::Level::Default,
+            CompressLevel::Max => json_packer::Level::Max,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum NonFiniteRepr {
+    #[value(name = "string")]
+    String,
+    #[value(name = "null")]
+    Null,
+}
+
+impl From<NonFiniteRepr> for json_packer::NonFinite {
+    fn from(repr: NonFiniteRepr) -> Self {
+        match repr {
+            NonFiniteRepr::String => json_packer::NonFinite::Sentinel,
+            NonFiniteRepr::Null => json_packer::NonFinite::Null,
+        }
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum, PartialEq)]
 pub enum InputFormat {
     #[value(name = "auto")]
@@ -9,7 +9,7 @@ use indicatif::{ProgressBar, ProgressStyle};
 use crate::cli::{BatchArgs, BatchOperation};
 use crate::error::{CliError, Result};
 use crate::commands::{compress, decompress};
-use crate::cli::{CompressArgs, DecompressArgs, OutputFormat, InputFormat};
+use crate::cli::{CompressArgs, DecompressArgs, NonFiniteRepr, OutputFormat, InputFormat};
 
 pub fn run(args: BatchArgs, verbose: bool, quiet: bool) -> Result<()> {
     if verbose && !quiet {
@@ -204,10 +204,23 @@ fn process_single_file(
                 output: Some(output_path),
                 format: OutputFormat::Base64,
                 enable_pool: args.enable_pool,
-                pool_min_repeats: 3,
-                pool_min_string_len: 8,
+                pool_min_repeats: 0,
+                pool_min_string_len: 0,
+                metadata: Vec::new(),
+                dedup_subtrees: false,
+                subtree_min_nodes: 4,
+                huffman_refs: false,
+                value_dicts: false,
+                value_dict_max: 16,
+                level: None,
+                string_subtypes: false,
+                timestamps: false,
+                typed_arrays: false,
+                float_decimals: None,
+                float_digits: None,
                 pretty: false,
                 stats: false,
+                report: false,
             };
             
             compress::run(compress_args, verbose, true) // 强制quiet模式避免大量输出
@@ -219,6 +232,9 @@ fn process_single_file(
                 format: InputFormat::Auto,
                 pretty: false,
                 compact: false,
+                strict: false,
+                non_finite: NonFiniteRepr::String,
+                skip_extensions: false,
             };
             
             decompress::run(decompress_args, verbose, true) // 强制quiet模式避免大量输出
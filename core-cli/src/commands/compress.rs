@@ -1,8 +1,8 @@
-use json_packer::{compress_to_bytes, compress_to_base64, CompressOptions};
+use json_packer::{compress_to_bytes, compress_with_report, encode_base64, tune_options, CompressOptions, FloatPrecision, Metadata};
 use serde_json::Value;
 
 use crate::cli::{CompressArgs, OutputFormat};
-use crate::error::Result;
+use crate::error::{CliError, Result};
 use crate::utils::{io, stats};
 
 pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
@@ -15,11 +15,25 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
     let json_value: Value = serde_json::from_str(&input_content)?;
     
     // Build compression options
-    let compress_opts = CompressOptions {
-        enable_value_pool: args.enable_pool,
-        pool_min_repeats: args.pool_min_repeats,
-        pool_min_string_len: args.pool_min_string_len,
-    };
+    // JSON 输入无法表示 NaN/±Inf，脱敏、路径选项与扩展类型不经命令行设置
+    let compress_opts = CompressOptions::default()
+        .with_value_pool(args.enable_pool)
+        .with_pool_min_repeats(args.pool_min_repeats)
+        .with_pool_min_string_len(args.pool_min_string_len)
+        .with_metadata(parse_metadata(&args.metadata)?)
+        .with_subtree_pool(args.dedup_subtrees)
+        .with_pool_min_subtree_nodes(args.subtree_min_nodes)
+        .with_huffman_pool_refs(args.huffman_refs)
+        .with_value_dicts(args.value_dicts)
+        .with_value_dict_max_values(args.value_dict_max)
+        .with_string_subtypes(args.string_subtypes)
+        .with_timestamp_strings(args.timestamps)
+        .with_typed_arrays(args.typed_arrays)
+        .with_float_precision(args.float_decimals.map(FloatPrecision::Decimals).or(args.float_digits.map(FloatPrecision::Significant)));
+    
+    if compress_opts.float_precision.is_some() && !quiet {
+        eprintln!("Warning: lossy compression, floats are rounded and will not decompress to their original values");
+    }
     
     if verbose && !quiet {
         eprintln!("Compression options: {compress_opts:?}");
@@ -31,9 +45,26 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
     }
     
     // Execute compression
+    let compress_opts = match args.level {
+        Some(level) => {
+            let tuned = tune_options(&json_value, level.into(), &compress_opts)?;
+            if verbose && !quiet {
+                eprintln!("Selected options: {tuned:?}");
+            }
+            tuned
+        }
+        None => compress_opts,
+    };
+    // 报告取自实际写出的字节，只编码一次
+    let (compressed_bytes, report) = if args.report {
+        let (bytes, report) = compress_with_report(&json_value, &compress_opts)?;
+        (bytes, Some(report))
+    } else {
+        (compress_to_bytes(&json_value, &compress_opts)?, None)
+    };
     let result = match args.format {
         OutputFormat::Base64 => {
-            let compressed = compress_to_base64(&json_value, &compress_opts)?;
+            let compressed = encode_base64(&compressed_bytes);
             if args.pretty {
                 // Add line breaks to Base64 for readability
                 format_base64_pretty(&compressed)
@@ -42,7 +73,6 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
             }
         }
         OutputFormat::Bytes => {
-            let compressed_bytes = compress_to_bytes(&json_value, &compress_opts)?;
             // For byte output, we output hex representation or write directly to file
             if args.output.is_some() {
                 // If there's an output file, write bytes directly
@@ -54,6 +84,9 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
                     let stats = stats::CompressionStats::new(original_size, compressed_size);
                     stats.print();
                 }
+                if let Some(report) = report.as_ref().filter(|_| !quiet) {
+                    stats::print_report(report);
+                }
                 
                 if verbose && !quiet {
                     eprintln!("Compression completed, written to: {}", args.output.as_deref().unwrap());
@@ -79,6 +112,9 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
         let stats = stats::CompressionStats::new(original_size, compressed_size);
         stats.print();
     }
+    if let Some(report) = report.as_ref().filter(|_| !quiet) {
+        stats::print_report(report);
+    }
     
     if verbose && !quiet {
         match &args.output {
@@ -90,6 +126,16 @@ pub fn run(args: CompressArgs, verbose: bool, quiet: bool) -> Result<()> {
     Ok(())
 }
 
+fn parse_metadata(pairs: &[String]) -> Result<Metadata> {
+    let mut metadata = Metadata::new();
+    for pair in pairs {
+        let (key, value) = pair.split_once('=')
+            .ok_or_else(|| CliError::InvalidArgs(format!("Metadata must be KEY=VALUE, got: {pair}")))?;
+        metadata.insert(key.to_string(), value.to_string());
+    }
+    Ok(metadata)
+}
+
 fn format_base64_pretty(base64: &str) -> String {
     const LINE_LENGTH: usize = 76;
     let mut result = String::new();
@@ -1,4 +1,4 @@
-use json_packer::{decompress_from_bytes, decompress_from_base64};
+use json_packer::{decode_base64, decompress_with_options, DecodeOptions, UnknownExtension};
 
 use crate::cli::{DecompressArgs, InputFormat};
 use crate::error::{CliError, Result};
@@ -17,14 +17,19 @@ pub fn run(args: DecompressArgs, verbose: bool, quiet: bool) -> Result<()> {
         io::read_input(&args.input)?.into_bytes()
     };
     
+    let decode_opts = DecodeOptions::default()
+        .with_strict(args.strict)
+        .with_non_finite(args.non_finite.into())
+        .with_unknown_extensions(if args.skip_extensions { UnknownExtension::Null } else { UnknownExtension::Error });
+    
     // Parse input according to format
     let decompressed_value = match args.format {
         InputFormat::Auto => {
             // Auto-detect format
             let detected_format = format::detect_format(&input_data);
-            decompress_with_format(&input_data, detected_format, verbose, quiet)?
+            decompress_with_format(&input_data, detected_format, &decode_opts, verbose, quiet)?
         }
-        format => decompress_with_format(&input_data, format, verbose, quiet)?
+        format => decompress_with_format(&input_data, format, &decode_opts, verbose, quiet)?
     };
     
     // Format JSON output
@@ -50,6 +55,7 @@ pub fn run(args: DecompressArgs, verbose: bool, quiet: bool) -> Result<()> {
 fn decompress_with_format(
     data: &[u8], 
     format: InputFormat, 
+    decode_opts: &DecodeOptions,
     verbose: bool, 
     quiet: bool
 ) -> Result<serde_json::Value> {
@@ -61,14 +67,16 @@ fn decompress_with_format(
             let data_str = String::from_utf8(data.to_vec())
                 .map_err(|e| CliError::InvalidFormat(format!("Invalid UTF-8 in Base64 input: {e}")))?;
             
-            decompress_from_base64(&data_str)
+            let bytes = decode_base64(&data_str)
+                .map_err(CliError::Compression)?;
+            decompress_with_options(&bytes, decode_opts)
                 .map_err(CliError::Compression)
         }
         InputFormat::Bytes => {
             if verbose && !quiet {
                 eprintln!("Using byte format for decompression");
             }
-            decompress_from_bytes(data)
+            decompress_with_options(data, decode_opts)
                 .map_err(CliError::Compression)
         }
         InputFormat::Auto => {
@@ -0,0 +1,23 @@
+use json_packer::disassemble;
+
+use crate::cli::DumpArgs;
+use crate::commands::info::read_compressed_input;
+use crate::error::{CliError, Result};
+use crate::utils::io;
+
+pub fn run(args: DumpArgs, verbose: bool, quiet: bool) -> Result<()> {
+    if verbose && !quiet {
+        eprintln!("Disassembling: {}", args.input);
+    }
+    
+    let compressed_bytes = read_compressed_input(&args.input, args.format)?;
+    
+    // Walk the bitstream up to the first error
+    let dump = disassemble(&compressed_bytes);
+    io::write_output(None, &dump.to_string())?;
+    
+    match dump.error {
+        Some(err) => Err(CliError::InvalidFormat(format!("decoding failed at bit {}: {}", err.bit_offset, err.error))),
+        None => Ok(()),
+    }
+}
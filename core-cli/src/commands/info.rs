@@ -1,5 +1,4 @@
-use json_packer::decompress_from_bytes;
-use json_packer::test_expose::{BitReader, read_header};
+use json_packer::{decompress_from_bytes, inspect};
 
 use crate::cli::{InfoArgs, InputFormat};
 use crate::error::{CliError, Result};
@@ -10,22 +9,8 @@ pub fn run(args: InfoArgs, verbose: bool, quiet: bool) -> Result<()> {
         eprintln!("Analyzing file: {}", args.input);
     }
     
-    // Read input data
-    let input_data = if args.format == InputFormat::Bytes || 
-                      (args.format == InputFormat::Auto && is_binary_file(&args.input)) {
-        io::read_input_bytes(&args.input)?
-    } else {
-        io::read_input(&args.input)?.into_bytes()
-    };
-    
-    // Parse input format
-    let compressed_bytes = match args.format {
-        InputFormat::Auto => {
-            let detected_format = format::detect_format(&input_data);
-            parse_input_for_info(&input_data, detected_format)?
-        }
-        format => parse_input_for_info(&input_data, format)?
-    };
+    // Read and parse input data
+    let compressed_bytes = read_compressed_input(&args.input, args.format)?;
     
     // Analyze compressed file
     let file_info = analyze_compressed_file(&compressed_bytes)?;
@@ -45,6 +30,24 @@ pub fn run(args: InfoArgs, verbose: bool, quiet: bool) -> Result<()> {
     Ok(())
 }
 
+/// Read compressed input and resolve it to raw bytes (shared with `dump`)
+pub fn read_compressed_input(input: &str, format: InputFormat) -> Result<Vec<u8>> {
+    let input_data = if format == InputFormat::Bytes || 
+                      (format == InputFormat::Auto && is_binary_file(input)) {
+        io::read_input_bytes(input)?
+    } else {
+        io::read_input(input)?.into_bytes()
+    };
+    
+    match format {
+        InputFormat::Auto => {
+            let detected_format = format::detect_format(&input_data);
+            parse_input_for_info(&input_data, detected_format)
+        }
+        format => parse_input_for_info(&input_data, format)
+    }
+}
+
 fn parse_input_for_info(data: &[u8], format: InputFormat) -> Result<Vec<u8>> {
     match format {
         InputFormat::Base64 => {
@@ -64,14 +67,14 @@ fn parse_input_for_info(data: &[u8], format: InputFormat) -> Result<Vec<u8>> {
 }
 
 fn analyze_compressed_file(data: &[u8]) -> Result<stats::FileInfo> {
-    // Parse file header
-    let mut reader = BitReader::new(data);
-    let header = read_header(&mut reader)
+    // Parse package layout
+    let info = inspect(data)
         .map_err(CliError::Compression)?;
     
-    let version = match header.version {
+    let version = match info.version {
         1 => "v1 (no value pool)".to_string(),
         2 => "v2 (value pool enabled)".to_string(),
+        3 => "v3 (extended header)".to_string(),
         v => format!("v{v} (unknown version)"),
     };
     
@@ -92,13 +95,25 @@ fn analyze_compressed_file(data: &[u8]) -> Result<stats::FileInfo> {
             (None, None)
         };
     
+    let sections = vec![
+        ("header", info.header),
+        ("dictionary", info.dict),
+        ("value pool", info.pool_section),
+        ("value dicts", info.value_dicts),
+        ("body", info.body),
+    ];
+    
     let file_info = stats::FileInfo {
         version,
         original_size_estimate,
         compressed_size: data.len() as u64,
-        dictionary_size: if header.dict_len > 0 { Some(header.dict_len as usize) } else { None },
-        pool_size: if header.pool_len > 0 { Some(header.pool_len as usize) } else { None },
+        dictionary_size: if info.keys.is_empty() { None } else { Some(info.keys.len()) },
+        pool_size: if info.pool.is_empty() { None } else { Some(info.pool.len()) },
         compression_ratio,
+        lossy: info.is_lossy(),
+        metadata: info.metadata.into_iter().collect(),
+        keys: info.keys,
+        sections,
     };
     
     Ok(file_info)
@@ -1,4 +1,5 @@
 pub mod compress;
 pub mod decompress;
 pub mod info;
-pub mod batch;
\ No newline at end of file
+pub mod batch;
+pub mod dump;
\ No newline at end of file
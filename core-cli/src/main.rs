@@ -22,5 +22,6 @@ fn run(cli: Cli) -> Result<()> {
         Commands::Decompress(args) => commands::decompress::run(args, cli.verbose, cli.quiet),
         Commands::Info(args) => commands::info::run(args, cli.verbose, cli.quiet),
         Commands::Batch(args) => commands::batch::run(args, cli.verbose, cli.quiet),
+        Commands::Dump(args) => commands::dump::run(args, cli.verbose, cli.quiet),
     }
 }
\ No newline at end of file
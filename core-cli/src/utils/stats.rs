@@ -1,5 +1,6 @@
 use serde_json::Value;
 use std::collections::HashMap;
+use json_packer::{CompressionReport, KeyInfo, SectionInfo, ValueSizes};
 use crate::utils::format::format_size;
 
 #[derive(Debug)]
@@ -45,6 +46,34 @@ impl CompressionStats {
     }
 }
 
+/// 打印核心库的压缩报告：区段位数、键、值池收益与各类值的编码大小
+pub fn print_report(report: &CompressionReport) {
+    let sizes = &report.sizes;
+    println!("Compression Report:");
+    println!("  Sections (bits):");
+    for (name, bits) in [
+        ("header", sizes.header_bits),
+        ("dictionary", sizes.dict_bits),
+        ("pool", sizes.pool_bits),
+        ("value dicts", sizes.value_dicts_bits),
+        ("body", sizes.body_bits),
+    ] {
+        println!("    {name:<12} {bits:>10}");
+    }
+    println!("  Keys: {} ({} written, avg code length {:.2} bits)", report.keys, report.key_refs, report.avg_key_code_len);
+    println!("  Pool: {} entries, {} hits, {} bits saved", report.pool_entries, report.pool_hits, report.pool_bits_saved);
+    for (name, values) in [("Ints", &report.ints), ("Floats", &report.floats), ("Strings", &report.strings), ("Bools", &report.bools)] {
+        print_value_sizes(name, values);
+    }
+}
+
+fn print_value_sizes(name: &str, values: &ValueSizes) {
+    println!("  {}: {} ({} bits)", name, values.count, values.bits);
+    for (bits, count) in &values.by_bits {
+        println!("    {bits:>6} bits × {count}");
+    }
+}
+
 #[derive(Debug)]
 pub struct FileInfo {
     pub version: String,
@@ -53,6 +82,11 @@ pub struct FileInfo {
     pub dictionary_size: Option<usize>,
     pub pool_size: Option<usize>,
     pub compression_ratio: Option<f64>,
+    /// 浮点数已量化（有损）
+    pub lossy: bool,
+    pub metadata: Vec<(String, String)>,
+    pub keys: Vec<KeyInfo>,
+    pub sections: Vec<(&'static str, SectionInfo)>,
 }
 
 impl FileInfo {
@@ -68,6 +102,17 @@ impl FileInfo {
             }
         }
         
+        if self.lossy {
+            println!("  Lossy: yes (floats are quantized)");
+        }
+        
+        if !self.metadata.is_empty() {
+            println!("  Metadata:");
+            for (key, value) in &self.metadata {
+                println!("    {key}: {value}");
+            }
+        }
+        
         if detailed {
             if let Some(dict_size) = self.dictionary_size {
                 println!("  Dictionary size: {dict_size} keys");
@@ -75,6 +120,18 @@ impl FileInfo {
             if let Some(pool_size) = self.pool_size {
                 println!("  Value pool size: {pool_size} strings");
             }
+            
+            println!("  Sections (bit offset / bits):");
+            for (name, section) in &self.sections {
+                println!("    {:<12} {:>10} {:>10}", name, section.bit_offset, section.bit_len);
+            }
+            
+            if !self.keys.is_empty() {
+                println!("  Dictionary keys (frequency / code length):");
+                for key in &self.keys {
+                    println!("    \"{}\" × {} ({} bits)", key.key, key.frequency, key.code_len);
+                }
+            }
         }
     }
 }
@@ -149,4 +149,186 @@ fn test_invalid_json() {
         .failure()
         .code(3) // JSON parsing error code
         .stderr(predicate::str::contains("JSON parsing error"));
-}
\ No newline at end of file
+}
+#[test]
+fn test_compress_with_metadata_and_info() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.jcp");
+    fs::write(&input_file, r#"{"id": 1}"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg("--meta").arg("producer=cli-test")
+        .arg("--meta").arg("schema=2")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("info").arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("v3"))
+        .stdout(predicate::str::contains("producer: cli-test"));
+}
+
+#[test]
+fn test_compress_level_max_roundtrip() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.jcp");
+    let json = r#"[{"status": "ok", "tags": ["a", "b"]}, {"status": "ok", "tags": ["a", "b"]}]"#;
+    fs::write(&input_file, json).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("-v").arg("compress")
+        .arg("--level").arg("max")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Selected options"));
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("decompress").arg("--compact").arg(compressed_file.to_str().unwrap());
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let restored: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let original: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(restored, original);
+}
+
+#[test]
+fn test_compress_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.jcp");
+    fs::write(&input_file, r#"[{"name": "same", "n": 1.5}, {"name": "same", "n": 2}]"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg("--enable-pool")
+        .args(["--pool-min-repeats", "0", "--pool-min-string-len", "0"])
+        .arg("--report")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Compression Report"))
+        .stdout(predicate::str::contains("Floats: 1"))
+        .stdout(predicate::str::contains("Pool: 1 entries, 2 hits"));
+}
+
+#[test]
+fn test_info_detailed_sections_and_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.jcp");
+    fs::write(&input_file, r#"[{"name": "a"}, {"name": "b"}]"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("info").arg("--detailed").arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Sections"))
+        .stdout(predicate::str::contains("\"name\" × 2"));
+}
+
+#[test]
+fn test_dump_command() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.jcp");
+    fs::write(&input_file, r#"{"name": "John", "age": 30}"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("dump").arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("header.version = 1"))
+        .stdout(predicate::str::contains("key \"name\""))
+        .stdout(predicate::str::contains("string \"John\""));
+}
+
+#[test]
+fn test_dump_marks_failing_position() {
+    let temp_dir = TempDir::new().unwrap();
+    let broken_file = temp_dir.path().join("broken.bin");
+    // 合法头 + 空字典 + 截断的字符串
+    fs::write(&broken_file, b"JCPR\x01\x00\x00\x05\x09ab").unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("dump").arg(broken_file.to_str().unwrap());
+    cmd.assert()
+        .failure()
+        .code(5)
+        .stdout(predicate::str::contains("!! error"));
+}
+
+#[test]
+fn test_decompress_strict_rejects_trailing_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.bin");
+    fs::write(&input_file, r#"{"id": 1}"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg("--format").arg("bytes")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    let mut bytes = fs::read(&compressed_file).unwrap();
+    bytes.push(0xAB);
+    fs::write(&compressed_file, bytes).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("decompress").arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("decompress").arg("--strict").arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .failure()
+        .code(4)
+        .stderr(predicate::str::contains("trailing data"));
+}
+
+#[test]
+fn test_decompress_truncated_reports_location() {
+    let temp_dir = TempDir::new().unwrap();
+    let input_file = temp_dir.path().join("test.json");
+    let compressed_file = temp_dir.path().join("test.bin");
+    fs::write(&input_file, r#"{"name": "a fairly long string value"}"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("compress")
+        .arg("--format").arg("bytes")
+        .arg(input_file.to_str().unwrap())
+        .arg(compressed_file.to_str().unwrap());
+    cmd.assert().success();
+
+    // 解码错误带位偏移与路径；越界读取报告为 truncated data
+    let bytes = fs::read(&compressed_file).unwrap();
+    fs::write(&compressed_file, &bytes[..bytes.len() - 4]).unwrap();
+    let mut cmd = Command::cargo_bin("json-packer-cli").unwrap();
+    cmd.arg("decompress").arg(compressed_file.to_str().unwrap());
+    cmd.assert()
+        .failure()
+        .code(4)
+        .stderr(predicate::str::contains("truncated data (at bit"))
+        .stderr(predicate::str::contains("path /name"));
+}
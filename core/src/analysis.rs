@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{extension::Extension, path_options::StringPolicy, redact::{self, Redactions}};
+
+/// 单次遍历统计：对象键频次与字符串值出现次数，按需附带子树结构哈希。
+/// 借用原文档中的 `&str`，不复制字符串；字典与值池均由此结果构建。
+#[derive(Debug, Default)]
+pub(crate) struct Analysis<'a> {
+    pub keys: HashMap<&'a str, u64>,
+    pub strings: HashMap<&'a str, u32>,
+    /// 节点数达到阈值的容器（对象/数组）：结构哈希 -> 互不相等的子树及其出现次数
+    pub subtrees: HashMap<u64, Vec<(&'a Value, u32)>>,
+    /// 上述容器的地址 -> 结构哈希，供选择阶段按位置查找
+    pub subtree_hashes: HashMap<*const Value, u64>,
+    /// 各键下直接字符串值的频次；取值种类超过上限的键被移除
+    pub key_values: HashMap<&'a str, HashMap<&'a str, u32>>,
+    /// 由扩展编解码器写出的值的地址；其内的键与字符串不计入统计
+    pub captured: HashSet<*const Value>,
+    count_strings: bool,
+    // None 表示不统计子树
+    min_subtree_nodes: Option<usize>,
+    // None 表示不统计键值分布
+    max_key_values: Option<usize>,
+    // 取值种类已超上限的键
+    key_values_overflow: HashSet<&'a str>,
+    // 按路径覆盖的字符串选项；None 表示全部按全局选项
+    policy: Option<&'a StringPolicy<'a>>,
+    // 脱敏规则；None 表示按原文档统计
+    redactions: Option<&'a Redactions>,
+    // 扩展编解码器；接受的值整体写为扩展值
+    extensions: &'a [Arc<dyn Extension>],
+}
+
+impl<'a> Analysis<'a> {
+    /// `count_strings` 为 false 时跳过字符串统计（未启用值池）
+    pub fn new(count_strings: bool) -> Self {
+        Self { count_strings, ..Default::default() }
+    }
+
+    /// 按上次统计规模预分配，减少重复扩容
+    pub fn with_capacity(count_strings: bool, keys: usize, strings: usize) -> Self {
+        let strings = if count_strings { strings } else { 0 };
+        Self { keys: HashMap::with_capacity(keys), strings: HashMap::with_capacity(strings), count_strings, ..Default::default() }
+    }
+
+    /// 同时统计节点数不少于 `min_nodes` 的相同子树
+    pub fn with_subtrees(mut self, min_nodes: usize) -> Self {
+        self.min_subtree_nodes = Some(min_nodes.max(1));
+        self
+    }
+
+    /// 同时统计各键下直接字符串值的分布（只保留取值不超过 `max_values` 种的键）
+    pub fn with_key_values(mut self, max_values: usize) -> Self {
+        self.max_key_values = Some(max_values);
+        self
+    }
+
+    /// 按路径规则决定各位置的字符串是否计入值池统计与值字典
+    pub fn with_policy(mut self, policy: Option<&'a StringPolicy<'a>>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 按脱敏结果统计：被移除的成员不计入，被替换的值按替换后的值统计，含命中位置的容器不参与子树去重
+    pub fn with_redactions(mut self, redactions: Option<&'a Redactions>) -> Self {
+        self.redactions = redactions;
+        self
+    }
+
+    /// 跳过被扩展编解码器接受的值，含此类值的容器不参与子树去重
+    pub fn with_extensions(mut self, extensions: &'a [Arc<dyn Extension>]) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    pub fn redactions(&self) -> Option<&'a Redactions> {
+        self.redactions
+    }
+
+    pub fn policy(&self) -> Option<&'a StringPolicy<'a>> {
+        self.policy
+    }
+
+    /// 逐事件累加统计（供 `PackWriter`）：一个对象键
+    pub fn add_key(&mut self, key: &'a str) {
+        *self.keys.entry(key).or_insert(0) += 1;
+    }
+
+    /// 逐事件累加统计：一个字符串值，`key` 为其所在成员的键（数组元素与根值为 None）
+    pub fn add_string(&mut self, key: Option<&'a str>, s: &'a str) {
+        if self.count_strings { *self.strings.entry(s).or_insert(0) += 1; }
+        if let (Some(max), Some(key)) = (self.max_key_values, key) { self.add_key_value(key, s, max); }
+    }
+
+    fn add_key_value(&mut self, key: &'a str, value: &'a str, max: usize) {
+        if self.key_values_overflow.contains(key) { return; }
+        let values = self.key_values.entry(key).or_default();
+        if let Some(count) = values.get_mut(value) {
+            *count += 1;
+        } else if values.len() < max {
+            values.insert(value, 1);
+        } else {
+            self.key_values.remove(key);
+            self.key_values_overflow.insert(key);
+        }
+    }
+
+    /// 累加一个文档的统计（可对多个样本多次调用）
+    pub fn add(&mut self, value: &'a Value) {
+        self.visit(value);
+    }
+
+    /// 返回 (结构哈希, 节点数)；未统计子树时哈希恒为 0
+    fn visit(&mut self, value: &'a Value) -> (u64, usize) {
+        if self.extensions.iter().any(|ext| ext.encode(value).is_some()) {
+            // 数据区只写载荷；所在容器已不参与子树去重，哈希无需区分
+            self.captured.insert(value as *const Value);
+            return (0, 1);
+        }
+        let track = self.min_subtree_nodes.is_some();
+        let captured = self.captured.len();
+        let mut h = DefaultHasher::new();
+        let mut nodes = 1;
+        match value {
+            Value::Object(map) => {
+                if track { (7u8, map.len()).hash(&mut h); }
+                for (key, val) in redact::members(self.redactions, map) {
+                    self.add_key(key);
+                    let (child, n) = self.visit(val);
+                    nodes += n;
+                    if track { (key, child).hash(&mut h); }
+                    if self.captured.contains(&(val as *const Value)) { continue; }
+                    if let (Some(max), Value::String(s)) = (self.max_key_values, val) {
+                        if self.policy.is_some_and(|p| p.inline(val)) {
+                            // 值字典按键名建表：任一位置强制内联时该键不建表
+                            self.key_values.remove(key.as_str());
+                            self.key_values_overflow.insert(key);
+                        } else {
+                            self.add_key_value(key, s, max);
+                        }
+                    }
+                }
+            }
+            Value::Array(arr) => {
+                if track { (6u8, arr.len()).hash(&mut h); }
+                for item in redact::items(self.redactions, arr) {
+                    let (child, n) = self.visit(item);
+                    nodes += n;
+                    if track { child.hash(&mut h); }
+                }
+            }
+            Value::String(s) => {
+                if self.count_strings && self.policy.is_none_or(|p| p.poolable(value, s)) { *self.strings.entry(s.as_str()).or_insert(0) += 1; }
+                if track { (5u8, s).hash(&mut h); }
+            }
+            // 标量的文本形式足以区分 null/bool/各类数字
+            other => if track { other.to_string().hash(&mut h); },
+        }
+        if !track { return (0, nodes); }
+        let hash = h.finish();
+        if let Some(min) = self.min_subtree_nodes
+            && (value.is_object() || value.is_array())
+            && nodes >= min
+            && self.redactions.is_none_or(|r| !r.touches(value))
+            && self.captured.len() == captured
+        {
+            let group = self.subtrees.entry(hash).or_default();
+            match group.iter_mut().find(|entry| entry.0 == value) {
+                Some((_, count)) => *count += 1,
+                None => group.push((value, 1)),
+            }
+            self.subtree_hashes.insert(value as *const Value, hash);
+        }
+        (hash, nodes)
+    }
+}
@@ -5,11 +5,37 @@ pub struct BitWriter {
     buffer: Vec<u8>,
     bit_bucket: u64, // 暂存位（低位优先）
     bit_len: u8,     // 暂存位数量 [0, 64)
+    counting: bool,  // 仅计数：满 8 位的字节直接丢弃
+    dropped: usize,  // 计数模式下已丢弃的字节数
 }
 
 impl BitWriter {
     pub fn new() -> Self {
-        Self { buffer: Vec::new(), bit_bucket: 0, bit_len: 0 }
+        Self::from_vec(Vec::new())
+    }
+
+    /// 复用已有缓冲区，在其已有内容之后继续写入
+    pub fn from_vec(buffer: Vec<u8>) -> Self {
+        Self { buffer, bit_bucket: 0, bit_len: 0, counting: false, dropped: 0 }
+    }
+
+    /// 只统计位数、不保留输出的写入器，用于估算压缩大小
+    pub fn counting() -> Self {
+        Self { counting: true, ..Self::new() }
+    }
+
+    /// 已写入的位数（含 `from_vec` 传入的已有内容）
+    pub fn bit_position(&self) -> u64 {
+        (self.buffer.len() + self.dropped) as u64 * 8 + self.bit_len as u64
+    }
+
+    #[inline]
+    fn flush_byte(&mut self) {
+        if self.counting {
+            self.dropped += 1;
+        } else {
+            self.buffer.push((self.bit_bucket & 0xFF) as u8);
+        }
     }
 
     #[inline]
@@ -27,8 +53,7 @@ impl BitWriter {
 
             // 每满 8 位就落盘
             while self.bit_len >= 8 {
-                let byte = (self.bit_bucket & 0xFF) as u8;
-                self.buffer.push(byte);
+                self.flush_byte();
                 self.bit_bucket >>= 8;
                 self.bit_len -= 8;
             }
@@ -40,8 +65,7 @@ impl BitWriter {
 
     pub fn align_to_byte(&mut self) {
         if self.bit_len > 0 {
-            let byte = (self.bit_bucket & 0xFF) as u8;
-            self.buffer.push(byte);
+            self.flush_byte();
             self.bit_bucket = 0;
             self.bit_len = 0;
         }
@@ -52,10 +76,15 @@ impl BitWriter {
         self.buffer
     }
 
-    pub fn bytes_len(&self) -> usize { self.buffer.len() + if self.bit_len > 0 { 1 } else { 0 } }
+    pub fn bytes_len(&self) -> usize { self.buffer.len() + self.dropped + if self.bit_len > 0 { 1 } else { 0 } }
+}
+
+impl Default for BitWriter {
+    fn default() -> Self { Self::new() }
 }
 
 /// LSB-first 位序 BitReader
+#[derive(Clone)]
 pub struct BitReader<'a> {
     bytes: &'a [u8],
     byte_pos: usize,
@@ -79,6 +108,11 @@ impl<'a> BitReader<'a> {
         }
     }
 
+    /// 已消费的位数（即下一位的绝对位偏移）
+    pub fn bit_position(&self) -> usize {
+        self.byte_pos * 8 - self.bit_len as usize
+    }
+
     pub fn remaining_bits(&self) -> usize {
         (self.bytes.len() - self.byte_pos) * 8 + self.bit_len as usize
     }
@@ -108,4 +142,20 @@ impl<'a> BitReader<'a> {
 
     #[inline]
     pub fn read_byte(&mut self) -> Result<u8, Error> { Ok(self.read_bits(8)? as u8) }
+
+    /// 跳过 n 位（不逐位读取）
+    pub fn skip_bits(&mut self, n: usize) -> Result<(), Error> {
+        if n > self.remaining_bits() { return Err(Error::BitstreamOutOfBounds); }
+        if n < self.bit_len as usize {
+            self.bit_bucket >>= n;
+            self.bit_len -= n as u8;
+            return Ok(());
+        }
+        let rest = n - self.bit_len as usize;
+        self.bit_bucket = 0;
+        self.bit_len = 0;
+        self.byte_pos += rest / 8;
+        self.read_bits((rest % 8) as u32)?;
+        Ok(())
+    }
 }
@@ -0,0 +1,87 @@
+use serde_json::Value;
+
+use crate::{
+    decode::{self, DecodeOptions},
+    encode::{self, CompressOptions, SizeHints},
+    project,
+    shared::SharedDictionary,
+    visit::Visitor,
+    Error,
+};
+
+/// 可复用的压缩器：按上次的统计表规模预分配，键频与上次相同时复用键码表；
+/// 可选绑定预共享字典（复用其 Huffman 与值池）
+#[derive(Debug)]
+pub struct Compressor {
+    opts: CompressOptions,
+    shared: Option<SharedDictionary>,
+    hints: SizeHints,
+}
+
+impl Compressor {
+    pub fn new(opts: CompressOptions) -> Self {
+        Self { opts, shared: None, hints: SizeHints::reusable() }
+    }
+
+    /// 绑定预共享字典；文档含字典外的键时自动退回自包含格式
+    pub fn with_dictionary(opts: CompressOptions, dict: SharedDictionary) -> Self {
+        Self { opts, shared: Some(dict), hints: SizeHints::reusable() }
+    }
+
+    pub fn options(&self) -> &CompressOptions { &self.opts }
+
+    pub fn dictionary(&self) -> Option<&SharedDictionary> { self.shared.as_ref() }
+
+    pub fn compress(&mut self, value: &Value) -> Result<Vec<u8>, Error> {
+        let mut out = Vec::new();
+        self.compress_into(value, &mut out)?;
+        Ok(out)
+    }
+
+    /// 追加写入 `out` 末尾（不清空已有内容），便于复用输出缓冲或拼接多个压缩包
+    pub fn compress_into(&mut self, value: &Value, out: &mut Vec<u8>) -> Result<(), Error> {
+        encode::compress_append(value, &self.opts, self.shared.as_ref(), &mut self.hints, out)
+    }
+}
+
+impl Default for Compressor {
+    fn default() -> Self { Self::new(CompressOptions::default()) }
+}
+
+/// 可复用的解压器：持有解码选项与可选的预共享字典
+#[derive(Debug, Default)]
+pub struct Decompressor {
+    opts: DecodeOptions,
+    shared: Option<SharedDictionary>,
+}
+
+impl Decompressor {
+    pub fn new(opts: DecodeOptions) -> Self {
+        Self { opts, shared: None }
+    }
+
+    pub fn with_dictionary(opts: DecodeOptions, dict: SharedDictionary) -> Self {
+        Self { opts, shared: Some(dict) }
+    }
+
+    pub fn options(&self) -> &DecodeOptions { &self.opts }
+
+    pub fn decompress(&self, bytes: &[u8]) -> Result<Value, Error> {
+        decode::decompress_with(bytes, &self.opts, self.shared.as_ref())
+    }
+
+    /// 事件式解码，不构建 `Value`（适用严格模式与预共享字典）
+    pub fn decompress_with_visitor<V: Visitor + ?Sized>(&self, bytes: &[u8], visitor: &mut V) -> Result<(), Error> {
+        decode::visit_with(bytes, &self.opts, self.shared.as_ref(), visitor)
+    }
+
+    /// 按投影路径解码，只构建匹配的子树
+    pub fn decompress_projected(&self, bytes: &[u8], patterns: &[&str]) -> Result<Value, Error> {
+        project::decompress_projected_with(bytes, patterns, &self.opts, self.shared.as_ref())
+    }
+
+    /// 解码缓冲区开头的一个压缩包，返回值及占用字节数
+    pub fn decompress_prefix(&self, bytes: &[u8]) -> Result<(Value, usize), Error> {
+        decode::decompress_prefix_with(bytes, &self.opts, self.shared.as_ref())
+    }
+}
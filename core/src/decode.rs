@@ -1,96 +1,444 @@
-use serde_json::{Map, Value};
+use std::cell::{Cell, OnceCell};
+use std::sync::Arc;
+
+use serde_json::Value;
 
 use crate::{
     bitstream::BitReader,
     dict,
-    header,
-    huffman::HuffmanCodec,
+    header::{self, PackageHeader},
+    huffman::{HuffmanCodec, RefCodec},
     types::tag,
     varint,
+    error::pointer_segment,
+    extension::{self, Codecs, Extension, UnknownExtension},
+    shared::SharedDictionary,
+    value_dict::ValueDicts,
+    non_finite::NonFinite,
+    quantize,
+    subtype::StringMarks,
+    typed_array::{self, Elem},
+    visit::{ValueBuilder, Visitor},
     Error,
 };
 
-fn decode_value(reader: &mut BitReader, huffman: &HuffmanCodec, version: u8, pool: Option<&Vec<Value>>) -> Result<Value, Error> {
+/// 数据区中哪些类型的值带 is_pool_ref 位，及引用 ID 的写法（由包头决定；值池条目自身均不带）
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PoolRefs<'c> {
+    pub strings: bool,
+    pub containers: bool,
+    /// POOL_REF_CODES 下的 ID Huffman 码；None 表示 ULEB128
+    pub ids: Option<&'c RefCodec>,
+}
+
+impl<'c> PoolRefs<'c> {
+    pub(crate) fn of(hdr: &PackageHeader, ids: Option<&'c RefCodec>) -> Self {
+        Self { strings: hdr.has_pool(), containers: hdr.has_subtree_refs(), ids }
+    }
+
+    pub(crate) fn read_id(&self, reader: &mut BitReader) -> Result<u64, Error> {
+        match self.ids {
+            Some(codes) => codes.decode(reader),
+            None => varint::read_uleb128(reader),
+        }
+    }
+}
+
+/// 值池条目：已全部解码，或按需解码（只解码被实际用到的条目）
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum PoolEntries<'a> {
+    Decoded(&'a [Value]),
+    Lazy(&'a LazyPool<'a>),
+}
+
+impl<'a> PoolEntries<'a> {
+    fn get(self, id: usize) -> Result<&'a Value, Error> {
+        match self {
+            PoolEntries::Decoded(entries) => entries.get(id).ok_or(Error::PoolIdOutOfRange),
+            PoolEntries::Lazy(pool) => pool.get(id),
+        }
+    }
+}
+
+/// 按需解码的值池：读取时只记录各条目的位偏移，首次引用时才解码并缓存
+#[derive(Debug)]
+pub(crate) struct LazyPool<'a> {
+    bytes: &'a [u8],
+    huffman: &'a HuffmanCodec,
+    offsets: Vec<usize>,
+    entries: Vec<OnceCell<Value>>,
+}
+
+impl<'a> LazyPool<'a> {
+    /// 从 `reader` 当前位置跳读值池区
+    pub(crate) fn read(reader: &mut BitReader, bytes: &'a [u8], hdr: &PackageHeader, huffman: &'a HuffmanCodec) -> Result<Option<Self>, Error> {
+        if !hdr.has_pool() { return Ok(None); }
+        let mut offsets = Vec::with_capacity((hdr.pool_len as usize).min(reader.remaining_bits() / 3));
+        for i in 0..hdr.pool_len {
+            offsets.push(reader.bit_position());
+            skip_value(reader, &BodyCtx::plain(huffman), None).map_err(|e| e.within(&format!("pool[{i}]")))?;
+        }
+        let entries = offsets.iter().map(|_| OnceCell::new()).collect();
+        Ok(Some(Self { bytes, huffman, offsets, entries }))
+    }
+
+    fn get(&self, id: usize) -> Result<&Value, Error> {
+        let cell = self.entries.get(id).ok_or(Error::PoolIdOutOfRange)?;
+        if let Some(value) = cell.get() { return Ok(value); }
+        let mut reader = BitReader::new(self.bytes);
+        reader.skip_bits(self.offsets[id])?;
+        let value = decode_value(&mut reader, &BodyCtx::plain(self.huffman), None).map_err(|e| e.within(&format!("pool[{id}]")))?;
+        Ok(cell.get_or_init(|| value))
+    }
+}
+
+/// 数据区解码上下文：键 Huffman、值池引用方式、值池条目与逐键值字典
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BodyCtx<'a> {
+    pub huffman: &'a HuffmanCodec,
+    pub refs: PoolRefs<'a>,
+    pub pool: Option<PoolEntries<'a>>,
+    pub values: Option<&'a ValueDicts>,
+    /// 内联字符串的时间戳/子类型标记（TIMESTAMPS、STRING_SUBTYPES）
+    pub marks: StringMarks<'a>,
+    /// 数组带类型标记（TYPED_ARRAYS）
+    pub typed_arrays: bool,
+    /// 浮点数带十进制标记（QUANTIZED_FLOATS）
+    pub quantized_floats: bool,
+    /// 浮点数可为 NaN/±Inf（NON_FINITE）时其在 `Value` 中的表示
+    pub non_finite: Option<NonFinite>,
+    /// null 标签后带扩展标记（EXTENSIONS）
+    pub extensions: Option<Codecs<'a>>,
+}
+
+impl<'a> BodyCtx<'a> {
+    /// 值池条目自身：不含引用，也不使用值字典
+    pub(crate) fn plain(huffman: &'a HuffmanCodec) -> Self {
+        Self { huffman, refs: PoolRefs::default(), pool: None, values: None, marks: StringMarks::default(), typed_arrays: false, quantized_floats: false, non_finite: None, extensions: None }
+    }
+
+    /// 读取值池引用 ID 并返回对应条目
+    pub(crate) fn pool_ref(&self, reader: &mut BitReader) -> Result<&'a Value, Error> {
+        let id = self.refs.read_id(reader)?;
+        self.pool_entry(id)
+    }
+
+    /// 按 ID 取值池条目
+    pub(crate) fn pool_entry(&self, id: u64) -> Result<&'a Value, Error> {
+        self.pool.ok_or(Error::PoolMissing)?.get(id as usize)
+    }
+
+    /// 校验读出的浮点数：未置位 NON_FINITE 时拒绝 NaN/±Inf
+    pub(crate) fn float(&self, f: f64) -> Result<f64, Error> {
+        if f.is_finite() || self.non_finite.is_some() { Ok(f) } else { Err(Error::IllegalFloat) }
+    }
+
+    /// 定型数组元素对应的 `Value`：浮点数同样校验并按 NaN/±Inf 表示策略转换
+    pub(crate) fn elem(&self, elem: Elem) -> Result<Value, Error> {
+        if let Elem::Float(f) = elem { self.float(f)?; }
+        Ok(elem.value(self.non_finite.unwrap_or_default()))
+    }
+
+    /// 读取数据区浮点数（float 标签之后）
+    pub(crate) fn read_float(&self, reader: &mut BitReader) -> Result<f64, Error> {
+        let f = if self.quantized_floats { quantize::read_float(reader)? } else { f64::from_bits(reader.read_bits(64)?) };
+        self.float(f)
+    }
+}
+
+/// 解码一个值；`member` 为所属键的值字典（仅对象成员的直接值）。
+/// 出错时附加当前位偏移（外层再逐级补全路径）
+pub(crate) fn decode_value(reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>) -> Result<Value, Error> {
+    let mut builder = ValueBuilder::new(ctx.non_finite.unwrap_or_default());
+    visit_value(reader, ctx, member, &mut builder, &mut Vec::new())?;
+    Ok(builder.finish())
+}
+
+/// 事件式解码一个值；`buf` 为字符串字节的复用缓冲
+pub(crate) fn visit_value<V: Visitor + ?Sized>(reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>, visitor: &mut V, buf: &mut Vec<u8>) -> Result<(), Error> {
+    visit_value_inner(reader, ctx, member, visitor, buf).map_err(|e| e.at(reader.bit_position() as u64))
+}
+
+fn visit_value_inner<V: Visitor + ?Sized>(reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>, visitor: &mut V, buf: &mut Vec<u8>) -> Result<(), Error> {
+    let (huffman, refs) = (ctx.huffman, ctx.refs);
+    visitor.bit_offset(reader.bit_position() as u64);
     let t = reader.read_bits(3)? as u8;
     match t {
-        tag::NULL => Ok(Value::Null),
-        tag::BOOL_FALSE => Ok(Value::Bool(false)),
-        tag::BOOL_TRUE => Ok(Value::Bool(true)),
+        tag::NULL => match ctx.extensions {
+            Some(codecs) if reader.read_bits(1)? == 1 => {
+                let (id, len) = extension::read_head(reader)?;
+                visitor.encoding(format_args!("extension type={id} len={len}"));
+                match codecs.decode(reader, id, len)? {
+                    Some(value) => visitor.extension(&value),
+                    None => visitor.value_null(),
+                }
+            }
+            _ => visitor.value_null(),
+        },
+        tag::BOOL_FALSE => visitor.value_bool(false),
+        tag::BOOL_TRUE => visitor.value_bool(true),
         tag::INT => {
             let is_unsigned = reader.read_bits(1)? as u8;
             if is_unsigned == 0 {
-                let i = varint::read_sleb128(reader)?;
-                Ok(Value::Number(i.into()))
+                visitor.value_int(varint::read_sleb128(reader)?);
             } else {
-                let u = varint::read_uleb128(reader)?;
-                // 尝试优先 as_u64 构造 JSON Number；serde_json::Number 支持 u64
-                Ok(serde_json::Number::from(u).into())
+                visitor.value_uint(varint::read_uleb128(reader)?);
             }
         }
-        tag::FLOAT => {
-            let bits = reader.read_bits(64)?;
-            let f = f64::from_bits(bits);
-            if !f.is_finite() { return Err(Error::IllegalFloat); }
-            Ok(serde_json::Number::from_f64(f).map(Value::Number).ok_or(Error::IllegalFloat)?)
-        }
+        tag::FLOAT => visitor.value_float(ctx.read_float(reader)?),
         tag::STRING => {
-            if version == header::VERSION_V2 {
+            // VALUE_DICTS：键有值字典时直接读该表的码字
+            if let Some(table) = member {
+                let s = table.decode_key_ref(reader)?;
+                visitor.encoding(format_args!("value dict"));
+                visitor.value_str(s);
+                return Ok(());
+            }
+            if refs.strings {
                 let is_ref = reader.read_bits(1)? as u8;
-                if is_ref == 1 {
-                    let id = varint::read_uleb128(reader)? as usize;
-                    let pool = pool.ok_or(Error::PoolMissing)?;
-                    let val = pool.get(id).ok_or(Error::PoolIdOutOfRange)?;
-                    return Ok(val.clone());
-                }
+                if is_ref == 1 { return visit_pool_ref(reader, ctx, visitor); }
+            }
+            if let Some(name) = ctx.marks.read(reader, buf)? { visitor.encoding(format_args!("{name}")); }
+            match std::str::from_utf8(buf) {
+                Ok(s) => visitor.value_str(s),
+                Err(_) => return Err(String::from_utf8(std::mem::take(buf)).unwrap_err().into()),
             }
-            let len = varint::read_uleb128(reader)? as usize;
-            let mut bytes = Vec::with_capacity(len);
-            for _ in 0..len { bytes.push(reader.read_byte()?); }
-            let s = String::from_utf8(bytes)?;
-            Ok(Value::String(s))
         }
         tag::ARRAY => {
+            if refs.containers && reader.read_bits(1)? == 1 { return visit_pool_ref(reader, ctx, visitor); }
+            let kind = if ctx.typed_arrays { typed_array::read_marker(reader)? } else { None };
             let count = varint::read_uleb128(reader)? as usize;
-            let mut arr = Vec::with_capacity(count);
-            for _ in 0..count { arr.push(decode_value(reader, huffman, version, pool)?); }
-            Ok(Value::Array(arr))
+            if let Some(kind) = kind { visitor.encoding(format_args!("{}", kind.name())); }
+            visitor.start_array(count);
+            if let Some(kind) = kind {
+                for i in 0..count {
+                    visitor.bit_offset(reader.bit_position() as u64);
+                    match kind.read(reader).map_err(|e| e.within(&format!("/{i}")))? {
+                        Elem::Float(f) => visitor.value_float(ctx.float(f).map_err(|e| e.within(&format!("/{i}")))?),
+                        Elem::Bool(b) => visitor.value_bool(b),
+                    }
+                }
+                visitor.end_array();
+                return Ok(());
+            }
+            for i in 0..count {
+                visit_value(reader, ctx, None, visitor, buf).map_err(|e| e.within(&format!("/{i}")))?;
+            }
+            visitor.end_array();
         }
         tag::OBJECT => {
+            if refs.containers && reader.read_bits(1)? == 1 { return visit_pool_ref(reader, ctx, visitor); }
             let count = varint::read_uleb128(reader)? as usize;
-            let mut map = Map::with_capacity(count);
+            visitor.start_object(count);
             for _ in 0..count {
-                let key = if huffman.try_get_code("").is_some() && false {
-                    // 不会进入，仅为避免单符号/空树误用；真实路径走 decode_key
-                    String::new()
-                } else {
-                    huffman.decode_key(reader)?
-                };
-                let val = decode_value(reader, huffman, version, pool)?;
-                map.insert(key, val);
+                visitor.bit_offset(reader.bit_position() as u64);
+                let key = huffman.decode_key_ref(reader)?;
+                visitor.key(key);
+                let table = ctx.values.and_then(|v| v.get(key));
+                visit_value(reader, ctx, table, visitor, buf).map_err(|e| e.within(&pointer_segment(key)))?;
             }
-            Ok(Value::Object(map))
+            visitor.end_object();
         }
-        _ => Err(Error::HuffmanError),
+        _ => unreachable!("tags are 3 bits"),
     }
+    Ok(())
+}
+
+/// 值池引用（is_pool_ref 位之后）：读取 ID 并交出对应条目
+fn visit_pool_ref<V: Visitor + ?Sized>(reader: &mut BitReader, ctx: &BodyCtx, visitor: &mut V) -> Result<(), Error> {
+    let id = ctx.refs.read_id(reader)?;
+    visitor.encoding(format_args!("ref #{id}"));
+    visitor.pooled(ctx.pool_entry(id)?);
+    Ok(())
+}
+
+/// 跳过一个值而不构建它：字符串按长度跳过，值池引用只读 ID、不解码条目
+pub(crate) fn skip_value(reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>) -> Result<(), Error> {
+    skip_value_inner(reader, ctx, member).map_err(|e| e.at(reader.bit_position() as u64))
+}
+
+fn skip_value_inner(reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>) -> Result<(), Error> {
+    let refs = ctx.refs;
+    let t = reader.read_bits(3)? as u8;
+    match t {
+        tag::NULL => if ctx.extensions.is_some() && reader.read_bits(1)? == 1 { extension::skip(reader)?; },
+        tag::BOOL_FALSE | tag::BOOL_TRUE => {}
+        tag::INT => {
+            if reader.read_bits(1)? == 0 { varint::read_sleb128(reader)?; } else { varint::read_uleb128(reader)?; }
+        }
+        tag::FLOAT => { ctx.read_float(reader)?; }
+        tag::STRING => {
+            if let Some(table) = member { table.decode_key_ref(reader)?; return Ok(()); }
+            if refs.strings && reader.read_bits(1)? == 1 { refs.read_id(reader)?; return Ok(()); }
+            return ctx.marks.skip(reader);
+        }
+        tag::ARRAY => {
+            if refs.containers && reader.read_bits(1)? == 1 { refs.read_id(reader)?; return Ok(()); }
+            let kind = if ctx.typed_arrays { typed_array::read_marker(reader)? } else { None };
+            let count = varint::read_uleb128(reader)?;
+            if let Some(kind) = kind { return kind.skip(reader, count); }
+            for i in 0..count {
+                skip_value(reader, ctx, None).map_err(|e| e.within(&format!("/{i}")))?;
+            }
+        }
+        tag::OBJECT => {
+            if refs.containers && reader.read_bits(1)? == 1 { refs.read_id(reader)?; return Ok(()); }
+            let count = varint::read_uleb128(reader)?;
+            for _ in 0..count {
+                let key = ctx.huffman.decode_key_ref(reader)?;
+                let table = ctx.values.and_then(|v| v.get(key));
+                skip_value(reader, ctx, table).map_err(|e| e.within(&pointer_segment(key)))?;
+            }
+        }
+        _ => unreachable!("tags are 3 bits"),
+    }
+    Ok(())
 }
 
 pub fn decode_json(reader: &mut BitReader) -> Result<Value, Error> {
+    decode_package(reader, &DecodeOptions::default(), None)
+}
+
+/// 解码一个完整压缩包；`shared` 为接收方持有的预共享字典
+pub(crate) fn decode_package(reader: &mut BitReader, opts: &DecodeOptions, shared: Option<&SharedDictionary>) -> Result<Value, Error> {
+    let mut builder = ValueBuilder::new(opts.non_finite);
+    visit_package(reader, opts, shared, &mut builder)?;
+    Ok(builder.finish())
+}
+
+/// 事件式解码一个完整压缩包；`opts` 提供扩展类型编解码器
+pub(crate) fn visit_package<V: Visitor + ?Sized>(reader: &mut BitReader, opts: &DecodeOptions, shared: Option<&SharedDictionary>, visitor: &mut V) -> Result<(), Error> {
     // 读包头
-    let hdr = header::read_header(reader)?;
+    let hdr = header::read_header(reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+    if hdr.flags & header::flags::SHARED_DICT != 0 {
+        // 预共享字典：校验指纹后直接使用其 Huffman 与值池
+        let dict = read_shared_fingerprint(reader, shared)?;
+        let pool = hdr.has_pool().then(|| PoolEntries::Decoded(dict.pool_values()));
+        let ids = read_ref_codes(reader, &hdr)?;
+        let values = read_value_dicts(reader, &hdr, dict.codec())?;
+        let last = Cell::new(0);
+        let ctx = BodyCtx { huffman: dict.codec(), refs: PoolRefs::of(&hdr, ids.as_ref()), pool, values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(opts.non_finite), extensions: Codecs::of(&hdr, &opts.extensions, opts.unknown_extensions) };
+        return visit_value(reader, &ctx, None, visitor, &mut Vec::new());
+    }
     // 读字典并构建 Huffman
-    let freq = dict::read_dictionary(reader)?;
+    let freq = dict::read_dictionary(reader).map_err(|e| e.at(reader.bit_position() as u64))?;
     let codec = HuffmanCodec::from_frequencies(&freq)?;
-    // 读值池（v2）
-    let pool: Option<Vec<Value>> = if hdr.version == header::VERSION_V2 {
-        let mut entries: Vec<Value> = Vec::with_capacity(hdr.pool_len as usize);
-        for _ in 0..hdr.pool_len { entries.push(decode_value(reader, &codec, header::VERSION_V1, None)?); }
-        Some(entries)
-    } else { None };
+    // 读值池（v2 / v3+VALUE_POOL）
+    let pool = read_pool(reader, &hdr, &codec)?;
+    let ids = read_ref_codes(reader, &hdr)?;
+    let values = read_value_dicts(reader, &hdr, &codec)?;
     // 读数据区
-    decode_value(reader, &codec, hdr.version, pool.as_ref())
+    let last = Cell::new(0);
+    let ctx = BodyCtx { huffman: &codec, refs: PoolRefs::of(&hdr, ids.as_ref()), pool: pool.as_deref().map(PoolEntries::Decoded), values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(opts.non_finite), extensions: Codecs::of(&hdr, &opts.extensions, opts.unknown_extensions) };
+    visit_value(reader, &ctx, None, visitor, &mut Vec::new())
+}
+
+/// 读取字典指纹并与接收方持有的预共享字典比对
+pub(crate) fn read_shared_fingerprint<'d>(reader: &mut BitReader, shared: Option<&'d SharedDictionary>) -> Result<&'d SharedDictionary, Error> {
+    let offset = reader.bit_position() as u64;
+    let found = reader.read_bits(32).map_err(|e| e.at(offset))? as u32;
+    let dict = shared.ok_or(Error::SharedDictionaryRequired(found)).map_err(|e| e.at(offset))?;
+    if dict.fingerprint() != found {
+        return Err(Error::SharedDictionaryMismatch { expected: dict.fingerprint(), found }.at(offset));
+    }
+    Ok(dict)
+}
+
+/// 读取值池区；未启用值池时返回 None
+pub(crate) fn read_pool(reader: &mut BitReader, hdr: &PackageHeader, codec: &HuffmanCodec) -> Result<Option<Vec<Value>>, Error> {
+    if !hdr.has_pool() { return Ok(None); }
+    let mut entries: Vec<Value> = Vec::with_capacity((hdr.pool_len as usize).min(reader.remaining_bits() / 3));
+    for i in 0..hdr.pool_len {
+        let entry = decode_value(reader, &BodyCtx::plain(codec), None).map_err(|e| e.within(&format!("pool[{i}]")))?;
+        entries.push(entry);
+    }
+    Ok(Some(entries))
+}
+
+/// 读取引用码长表（POOL_REF_CODES）：每个池条目一个 ULEB128 码长
+pub(crate) fn read_ref_codes(reader: &mut BitReader, hdr: &PackageHeader) -> Result<Option<RefCodec>, Error> {
+    if hdr.flags & header::flags::POOL_REF_CODES == 0 { return Ok(None); }
+    let offset = reader.bit_position() as u64;
+    let mut lengths = Vec::with_capacity((hdr.pool_len as usize).min(reader.remaining_bits() / 8));
+    for _ in 0..hdr.pool_len {
+        let len = varint::read_uleb128(reader).map_err(|e| e.at(offset))?;
+        lengths.push(RefCodec::code_len(len).map_err(|e| e.at(offset))?);
+    }
+    RefCodec::from_lengths(lengths).map(Some).map_err(|e| e.at(offset))
+}
+
+/// 读取逐键值字典区（VALUE_DICTS）
+pub(crate) fn read_value_dicts(reader: &mut BitReader, hdr: &PackageHeader, codec: &HuffmanCodec) -> Result<Option<ValueDicts>, Error> {
+    if hdr.flags & header::flags::VALUE_DICTS == 0 { return Ok(None); }
+    let offset = reader.bit_position() as u64;
+    ValueDicts::read(reader, codec).map(Some).map_err(|e| e.at(offset))
 }
 
 pub fn decompress_from_bytes(bytes: &[u8]) -> Result<Value, Error> {
     let mut reader = BitReader::new(bytes);
     decode_json(&mut reader)
+}
+
+pub(crate) fn decompress_with(bytes: &[u8], opts: &DecodeOptions, shared: Option<&SharedDictionary>) -> Result<Value, Error> {
+    let mut builder = ValueBuilder::new(opts.non_finite);
+    visit_with(bytes, opts, shared, &mut builder)?;
+    Ok(builder.finish())
+}
+
+pub(crate) fn visit_with<V: Visitor + ?Sized>(bytes: &[u8], opts: &DecodeOptions, shared: Option<&SharedDictionary>, visitor: &mut V) -> Result<(), Error> {
+    let mut reader = BitReader::new(bytes);
+    visit_package(&mut reader, opts, shared, visitor)?;
+    if opts.strict { check_trailing(&mut reader, bytes.len())?; }
+    Ok(())
+}
+
+/// 不检查尾随数据（`opts.strict` 不生效）
+pub(crate) fn decompress_prefix_with(bytes: &[u8], opts: &DecodeOptions, shared: Option<&SharedDictionary>) -> Result<(Value, usize), Error> {
+    let mut reader = BitReader::new(bytes);
+    let value = decode_package(&mut reader, opts, shared)?;
+    Ok((value, reader.bit_position().div_ceil(8)))
+}
+
+/// 严格模式：末字节剩余位为对齐填充且必须全为 0，其后不允许再有字节
+fn check_trailing(reader: &mut BitReader, total_len: usize) -> Result<(), Error> {
+    let end = reader.bit_position();
+    let pad = (8 - end % 8) % 8;
+    if pad > 0 && reader.read_bits(pad as u32)? != 0 {
+        return Err(Error::TrailingData { offset: (end / 8) as u64 });
+    }
+    let consumed = end.div_ceil(8);
+    if consumed < total_len {
+        return Err(Error::TrailingData { offset: consumed as u64 });
+    }
+    Ok(())
+}
+
+/// 解码选项（无状态，按调用传入）；可用结构体字面量（配合 `..Default::default()`）或 `with_*` 链式设置
+#[derive(Debug, Clone, Default)]
+pub struct DecodeOptions {
+    /// 严格模式：数据区之后除字节对齐填充（须为 0）外不允许任何尾随数据
+    pub strict: bool,
+    /// NON_FINITE 压缩包中 NaN/±Inf 的表示（默认为 JSON5 写法的字符串）
+    pub non_finite: NonFinite,
+    /// 扩展类型编解码器，按 `Extension::id` 匹配
+    pub extensions: Vec<Arc<dyn Extension>>,
+    /// 未注册的扩展类型：报错（默认）或解码为 null
+    pub unknown_extensions: UnknownExtension,
+}
+
+/// 链式设置，各方法对应同名字段
+impl DecodeOptions {
+    pub fn with_strict(mut self, strict: bool) -> Self { self.strict = strict; self }
+    pub fn with_non_finite(mut self, non_finite: NonFinite) -> Self { self.non_finite = non_finite; self }
+    pub fn with_extensions(mut self, extensions: Vec<Arc<dyn Extension>>) -> Self { self.extensions = extensions; self }
+    pub fn with_unknown_extensions(mut self, unknown_extensions: UnknownExtension) -> Self { self.unknown_extensions = unknown_extensions; self }
+}
+
+/// 解码位于缓冲区开头的一个压缩包，返回值及其占用的字节数（含末尾对齐填充）
+pub fn decompress_prefix(bytes: &[u8]) -> Result<(Value, usize), Error> {
+    decompress_prefix_with(bytes, &DecodeOptions::default(), None)
+}
+
+pub fn decompress_with_options(bytes: &[u8], opts: &DecodeOptions) -> Result<Value, Error> {
+    decompress_with(bytes, opts, None)
 }
\ No newline at end of file
@@ -1,50 +1,27 @@
 use std::collections::HashMap;
 use serde_json::Value;
-use crate::{bitstream::{BitWriter, BitReader}, varint, Error};
+use crate::{analysis::Analysis, bitstream::{BitWriter, BitReader}, varint, Error};
 
 /// 收集 JSON 中所有对象键的频率统计
 pub fn collect_keys(json: &Value) -> HashMap<String, u64> {
-    let mut freq_map = HashMap::new();
-    collect_keys_recursive(json, &mut freq_map);
-    freq_map
-}
-
-/// 递归遍历 JSON 值，统计对象键的频率
-fn collect_keys_recursive(value: &Value, freq_map: &mut HashMap<String, u64>) {
-    match value {
-        Value::Object(map) => {
-            // 统计当前对象的所有键
-            for (key, val) in map {
-                *freq_map.entry(key.clone()).or_insert(0) += 1;
-                // 递归处理值
-                collect_keys_recursive(val, freq_map);
-            }
-        },
-        Value::Array(arr) => {
-            // 递归处理数组中的每个元素
-            for item in arr {
-                collect_keys_recursive(item, freq_map);
-            }
-        },
-        _ => {
-            // 其他类型（null, bool, number, string）无需处理
-        }
-    }
+    let mut analysis = Analysis::new(false);
+    analysis.add(json);
+    analysis.keys.into_iter().map(|(k, f)| (k.to_string(), f)).collect()
 }
 
 /// 写入字典表到位流
 /// 格式：[KEY_COUNT(uleb128)] + 对每个键: [KEY_LEN(uleb128)][KEY_UTF8...][FREQ(uleb128)]
-pub fn write_dictionary(writer: &mut BitWriter, freq_map: &HashMap<String, u64>) {
+pub fn write_dictionary<K: AsRef<str>>(writer: &mut BitWriter, freq_map: &HashMap<K, u64>) {
     // 写入键的总数
     varint::write_uleb128(writer, freq_map.len() as u64);
     
     // 按字典序排序键名，确保确定性输出
     let mut sorted_keys: Vec<_> = freq_map.iter().collect();
-    sorted_keys.sort_by(|a, b| a.0.cmp(b.0));
+    sorted_keys.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
     
     // 写入每个键的信息
     for (key, &freq) in sorted_keys {
-        let key_bytes = key.as_bytes();
+        let key_bytes = key.as_ref().as_bytes();
         
         // 键长度 (ULEB128)
         varint::write_uleb128(writer, key_bytes.len() as u64);
@@ -71,8 +48,8 @@ pub fn read_dictionary(reader: &mut BitReader) -> Result<HashMap<String, u64>, E
         // 读取键长度
         let key_len = varint::read_uleb128(reader)? as usize;
         
-        // 读取键内容
-        let mut key_bytes = Vec::with_capacity(key_len);
+        // 读取键内容（声明长度不可信，预分配不超过剩余数据）
+        let mut key_bytes = Vec::with_capacity(key_len.min(reader.remaining_bits() / 8));
         for _ in 0..key_len {
             key_bytes.push(reader.read_byte()?);
         }
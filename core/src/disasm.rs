@@ -0,0 +1,270 @@
+use std::cell::Cell;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{
+    bitstream::BitReader,
+    decode::{self, BodyCtx, PoolEntries, PoolRefs},
+    dict,
+    extension::{Codecs, UnknownExtension},
+    header::{self, flags},
+    huffman::HuffmanCodec,
+    non_finite::NonFinite,
+    subtype::StringMarks,
+    visit::Visitor,
+    Error,
+};
+
+/// 反汇编清单中的一行：起始位偏移、占用位数、嵌套深度与注释文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DumpLine {
+    pub bit_offset: u64,
+    pub bit_len: u64,
+    pub depth: usize,
+    pub text: String,
+}
+
+/// 首个解码错误及其所在条目的起始位偏移
+#[derive(Debug)]
+pub struct DumpError {
+    pub bit_offset: u64,
+    pub error: Error,
+}
+
+/// 反汇编结果：出错前的全部清单行，以及（若有）首个错误
+#[derive(Debug)]
+pub struct Disassembly {
+    pub lines: Vec<DumpLine>,
+    pub error: Option<DumpError>,
+}
+
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>10} {:>6}  item", "bit", "bits")?;
+        for line in &self.lines {
+            writeln!(f, "{:>10} {:>6}  {}{}", line.bit_offset, line.bit_len, "  ".repeat(line.depth), line.text)?;
+        }
+        if let Some(err) = &self.error {
+            writeln!(f, "{:>10} {:>6}  !! error: {}", err.bit_offset, "-", err.error)?;
+        }
+        Ok(())
+    }
+}
+
+/// 逐区段读取压缩包（与解码器共用各区段读取函数）并生成带注释的清单；
+/// 数据区由解码器按事件回调，遇到首个错误即停止并标记其位置
+pub fn disassemble(bytes: &[u8]) -> Disassembly {
+    let mut reader = BitReader::new(bytes);
+    let mut listing = Listing::default();
+    let error = listing.run(&mut reader).err().map(|e| {
+        let bit_offset = e.bit_offset().unwrap_or(listing.start);
+        listing.close(bit_offset);
+        DumpError { bit_offset, error: e.into_kind() }
+    });
+    Disassembly { lines: listing.lines, error }
+}
+
+#[derive(Default)]
+struct Listing {
+    lines: Vec<DumpLine>,
+    depth: usize,
+    // 当前条目（或区段）起始位置
+    start: u64,
+    // 尚未确定位长度的数据区清单行，由下一条目的起始位置补全
+    open: Option<usize>,
+    // 随后一个值的编码细节
+    note: Option<String>,
+    // 每层容器一项：是否为对象成员（键与其值）
+    stack: Vec<bool>,
+}
+
+impl Listing {
+    fn run(&mut self, reader: &mut BitReader) -> Result<(), Error> {
+        // 包头
+        let hdr = header::read_header(reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+        self.section(0, reader, "header".to_string());
+        self.detail(1, format!("header.version = {}", hdr.version));
+        if hdr.version == header::VERSION_V3 { self.detail(1, format!("header.flags = {:#x}", hdr.flags)); }
+        if hdr.has_quantized_floats() { self.detail(1, "note: lossy package, floats are quantized".to_string()); }
+        self.detail(1, format!("header.dict_len = {}", hdr.dict_len));
+        self.detail(1, format!("header.pool_len = {}", hdr.pool_len));
+        for (k, v) in &hdr.metadata { self.detail(1, format!("header.metadata {k:?} = {v:?}")); }
+        if hdr.flags & flags::SHARED_DICT != 0 {
+            // 字典与值池在包外，无法继续解码数据区
+            self.start = reader.bit_position() as u64;
+            decode::read_shared_fingerprint(reader, None)?;
+        }
+
+        // 字典
+        let from = reader.bit_position();
+        let freq = dict::read_dictionary(reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+        let codec = HuffmanCodec::from_frequencies(&freq).map_err(|e| e.at(from as u64))?;
+        self.section(from, reader, format!("dict count={}", freq.len()));
+        let mut keys: Vec<_> = freq.iter().collect();
+        keys.sort();
+        for (i, (key, f)) in keys.into_iter().enumerate() { self.detail(1, format!("dict[{i}] {key:?} freq={f}")); }
+
+        // 值池
+        let from = reader.bit_position();
+        let pool = decode::read_pool(reader, &hdr, &codec)?;
+        if let Some(entries) = &pool {
+            self.section(from, reader, format!("pool count={}", entries.len()));
+            for (i, entry) in entries.iter().enumerate() { self.detail(1, format!("pool[{i}] {entry}")); }
+        }
+        // 引用码长表
+        let from = reader.bit_position();
+        let ids = decode::read_ref_codes(reader, &hdr)?;
+        if let Some(codes) = &ids {
+            self.section(from, reader, "pool.ref_codes".to_string());
+            for (i, len) in codes.lengths().iter().enumerate() { self.detail(1, format!("pool.ref_len[{i}] = {len}")); }
+        }
+
+        // 逐键值字典
+        let from = reader.bit_position();
+        let values = decode::read_value_dicts(reader, &hdr, &codec)?;
+        if let Some(dicts) = &values {
+            self.section(from, reader, format!("value_dicts count={}", dicts.keys().count()));
+            for key in dicts.keys() {
+                self.detail(1, format!("value_dict {key:?}"));
+                let mut entries: Vec<_> = dicts.frequencies(key).into_iter().flatten().collect();
+                entries.sort();
+                for (i, (v, f)) in entries.into_iter().enumerate() { self.detail(2, format!("[{i}] {v:?} freq={f}")); }
+            }
+        }
+
+        // 数据区：扩展值不解码，只列出类型与载荷长度
+        let from = reader.bit_position();
+        let body = self.lines.len();
+        self.section(from, reader, "body".to_string());
+        let last = Cell::new(0);
+        let ctx = BodyCtx { huffman: &codec, refs: PoolRefs::of(&hdr, ids.as_ref()), pool: pool.as_deref().map(PoolEntries::Decoded), values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(NonFinite::default()), extensions: Codecs::of(&hdr, &[], UnknownExtension::Null) };
+        self.depth = 1;
+        decode::visit_value(reader, &ctx, None, self, &mut Vec::new())?;
+        let end = reader.bit_position() as u64;
+        self.close(end);
+        self.lines[body].bit_len = end - from as u64;
+
+        let rest = reader.remaining_bits();
+        if rest > 0 {
+            self.lines.push(DumpLine { bit_offset: end, bit_len: rest as u64, depth: 0, text: format!("end: {rest} bits remain (padding or trailing data)") });
+        }
+        Ok(())
+    }
+
+    /// 区段行：覆盖 `from` 至当前读取位置
+    fn section(&mut self, from: usize, reader: &BitReader, text: String) {
+        let end = reader.bit_position();
+        self.start = from as u64;
+        self.lines.push(DumpLine { bit_offset: from as u64, bit_len: (end - from) as u64, depth: 0, text });
+    }
+
+    /// 区段内容的说明行，位置记在所属区段起点
+    fn detail(&mut self, depth: usize, text: String) {
+        self.lines.push(DumpLine { bit_offset: self.start, bit_len: 0, depth, text });
+    }
+
+    /// 补全上一数据区清单行的位长度
+    fn close(&mut self, end: u64) {
+        if let Some(i) = self.open.take() {
+            let line = &mut self.lines[i];
+            line.bit_len = end.saturating_sub(line.bit_offset);
+        }
+    }
+
+    fn push(&mut self, text: String) {
+        self.open = Some(self.lines.len());
+        self.lines.push(DumpLine { bit_offset: self.start, bit_len: 0, depth: self.depth, text });
+    }
+
+    /// 一个值结束；若为对象成员的值，同时结束该成员
+    fn done(&mut self) {
+        if self.stack.last() == Some(&true) {
+            self.stack.pop();
+            self.depth -= 1;
+        }
+    }
+
+    fn leaf(&mut self, text: String) {
+        let text = match self.note.take() {
+            Some(note) => format!("{text} ({note})"),
+            None => text,
+        };
+        self.push(text);
+        self.done();
+    }
+
+    fn open_container(&mut self, text: String) {
+        let text = match self.note.take() {
+            Some(note) => format!("{text} ({note})"),
+            None => text,
+        };
+        self.push(text);
+        self.depth += 1;
+        self.stack.push(false);
+    }
+
+    fn close_container(&mut self) {
+        self.stack.pop();
+        self.depth -= 1;
+        self.done();
+    }
+}
+
+/// 值池条目的简短展示，用于标注引用
+fn shown(value: &Value) -> (&'static str, String) {
+    match value {
+        Value::String(s) => ("string", format!("{s:?}")),
+        Value::Array(arr) => ("array", format!("[{} items]", arr.len())),
+        Value::Object(map) => ("object", format!("{{{} members}}", map.len())),
+        v => ("value", v.to_string()),
+    }
+}
+
+impl Visitor for Listing {
+    fn start_object(&mut self, len: usize) { self.open_container(format!("object len={len}")); }
+
+    fn key(&mut self, key: &str) {
+        self.push(format!("key {key:?}"));
+        self.depth += 1;
+        self.stack.push(true);
+    }
+
+    fn end_object(&mut self) { self.close_container(); }
+
+    fn start_array(&mut self, len: usize) { self.open_container(format!("array len={len}")); }
+
+    fn end_array(&mut self) { self.close_container(); }
+
+    fn value_null(&mut self) {
+        // 扩展值以 null 回调，编码细节即其类型与载荷长度
+        let text = self.note.take().unwrap_or_else(|| "null".to_string());
+        self.leaf(text);
+    }
+
+    fn value_bool(&mut self, value: bool) { self.leaf(value.to_string()); }
+
+    fn value_int(&mut self, value: i64) { self.leaf(format!("int {value}")); }
+
+    fn value_uint(&mut self, value: u64) { self.leaf(format!("int {value}u")); }
+
+    fn value_float(&mut self, value: f64) { self.leaf(format!("float {value:?}")); }
+
+    fn value_str(&mut self, value: &str) { self.leaf(format!("string {value:?}")); }
+
+    fn pooled(&mut self, value: &Value) {
+        let (kind, target) = shown(value);
+        let note = self.note.take().unwrap_or_default();
+        self.push(format!("{kind} {note} -> {target}"));
+        self.done();
+    }
+
+    fn extension(&mut self, _: &Value) { self.value_null(); }
+
+    fn bit_offset(&mut self, offset: u64) {
+        self.close(offset);
+        self.start = offset;
+    }
+
+    fn encoding(&mut self, detail: fmt::Arguments<'_>) { self.note = Some(detail.to_string()); }
+}
@@ -1,7 +1,12 @@
 use serde_json::Value;
-use crate::{bitstream::BitWriter, header, dict, huffman::HuffmanCodec, types::tag, varint, Error, pool::{collect_string_pool, PoolConfig, write_string_pool}};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::sync::Arc;
 
-fn encode_value(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec) -> Result<(), Error> {
+use crate::{analysis::Analysis, bitstream::BitWriter, header::{self, Metadata, PackageHeader}, dict, extension::{self, Extension}, huffman::{HuffmanCodec, RefCodec}, types::tag, varint, Error, pool::{select_pool, select_subtrees, PoolConfig, StringPool, SubtreePool, write_string_pool}, path_options::{PathOptions, StringPolicy}, quantize::{self, FloatPrecision}, redact::{self, RedactRule, Redactions}, report::BodyStats, shared::SharedDictionary, subtype::StringMarks, typed_array::{self, Elem, Kind}, value_dict::ValueDicts};
+
+pub(crate) fn encode_value(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec) -> Result<(), Error> {
     match value {
         Value::Null => {
             writer.write_bits(tag::NULL as u64, 3);
@@ -28,12 +33,7 @@ fn encode_value(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec) -
                 return Err(Error::IllegalFloat);
             }
         }
-        Value::String(s) => {
-            writer.write_bits(tag::STRING as u64, 3);
-            let bytes = s.as_bytes();
-            varint::write_uleb128(writer, bytes.len() as u64);
-            for &b in bytes { writer.write_byte(b); }
-        }
+        Value::String(s) => write_string(writer, s, false, StringMarks::default()),
         Value::Array(arr) => {
             writer.write_bits(tag::ARRAY as u64, 3);
             varint::write_uleb128(writer, arr.len() as u64);
@@ -51,87 +51,529 @@ fn encode_value(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec) -
     Ok(())
 }
 
+/// 内联字符串：tag + [is_pool_ref=0，仅启用值池时] + len + bytes；
+/// `marks` 按包头标志在 len + bytes 前加时间戳/子类型标记并换用紧凑载荷
+pub(crate) fn write_string(writer: &mut BitWriter, s: &str, pooled: bool, marks: StringMarks) {
+    writer.write_bits(tag::STRING as u64, 3);
+    if pooled { writer.write_bits(0, 1); }
+    marks.write(writer, s);
+}
 
+/// 压缩可选项；可用结构体字面量（配合 `..Default::default()`）或 `with_*` 链式设置
 #[derive(Debug, Clone)]
 pub struct CompressOptions {
     pub enable_value_pool: bool,
+    /// 入池字符串的最小重复次数（默认 3），与位代价模型同时生效；0 表示不设下限
     pub pool_min_repeats: u32,
+    /// 入池字符串的最小字节长度（默认 8）；0 表示不设下限
     pub pool_min_string_len: usize,
+    /// 写入包头的用户元数据；非空时输出 v3 格式
+    pub metadata: Metadata,
+    /// 子树去重：重复出现的对象/数组写入值池一次，其余位置以 ID 引用（v3）
+    pub enable_subtree_pool: bool,
+    /// 入池子树的最小节点数（容器自身及其全部后代值）
+    pub pool_min_subtree_nodes: usize,
+    /// 值池引用 ID 按引用频次做 canonical Huffman 编码（v3）；仅在比 ULEB128 更省时生效
+    pub huffman_pool_refs: bool,
+    /// 逐键值字典：取值种类少的键使用专属 Huffman 码表写其字符串值（v3）
+    pub enable_value_dicts: bool,
+    /// 建立值字典的键最多可有的不同字符串值数
+    pub value_dict_max_values: usize,
+    /// 脱敏规则：编码时移除、置空或哈希匹配路径上的值，被移除的键不进入字典
+    pub redact: Vec<RedactRule>,
+    /// 按路径覆盖值池资格、入池最小长度与字符串写法
+    pub path_options: Vec<PathOptions>,
+    /// 字符串子类型：数据区内联的 UUID、十六进制、Base64 与十进制串按紧凑形式写入（v3）
+    pub string_subtypes: bool,
+    /// 时间戳：数据区内联的 RFC 3339 时间串写为差分秒数 + 精度 + 偏移，解码还原原串（v3）
+    pub timestamp_strings: bool,
+    /// 定型数组：数据区中全为浮点（可无损时按 f32）或全为布尔的数组按紧凑块写入（v3）
+    pub typed_arrays: bool,
+    /// 有损浮点：按精度量化全部浮点数并以十进制缩放整数写入，包头标记为有损（v3）；None 为无损
+    pub float_precision: Option<FloatPrecision>,
+    /// 允许 NaN/±Inf：`PackWriter::float` 可写入非有限浮点数，包头标记 NON_FINITE（v3）；
+    /// 解码时按 `DecodeOptions::non_finite` 表示。仅对 `PackWriter` 有意义：`Value` 无法容纳
+    /// 非有限值，`compress_*` 等按 `Value` 压缩的接口不会把 `"NaN"` 等字符串还原为浮点数
+    pub non_finite_floats: bool,
+    /// 扩展类型编解码器：数据区中被某个编解码器接受的值写为扩展值（v3），先注册者优先；
+    /// 解码方须注册相同 ID 的编解码器，或选择跳过未知扩展
+    pub extensions: Vec<Arc<dyn Extension>>,
 }
 
 impl Default for CompressOptions {
-    fn default() -> Self { Self { enable_value_pool: false, pool_min_repeats: 3, pool_min_string_len: 8 } }
+    fn default() -> Self {
+        Self {
+            enable_value_pool: false,
+            pool_min_repeats: 3,
+            pool_min_string_len: 8,
+            metadata: Metadata::new(),
+            enable_subtree_pool: false,
+            pool_min_subtree_nodes: 4,
+            huffman_pool_refs: false,
+            enable_value_dicts: false,
+            value_dict_max_values: 16,
+            redact: Vec::new(),
+            path_options: Vec::new(),
+            string_subtypes: false,
+            timestamp_strings: false,
+            typed_arrays: false,
+            float_precision: None,
+            non_finite_floats: false,
+            extensions: Vec::new(),
+        }
+    }
+}
+
+/// 链式设置，各方法对应同名字段
+impl CompressOptions {
+    pub fn with_value_pool(mut self, enable_value_pool: bool) -> Self { self.enable_value_pool = enable_value_pool; self }
+    pub fn with_pool_min_repeats(mut self, pool_min_repeats: u32) -> Self { self.pool_min_repeats = pool_min_repeats; self }
+    pub fn with_pool_min_string_len(mut self, pool_min_string_len: usize) -> Self { self.pool_min_string_len = pool_min_string_len; self }
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self { self.metadata = metadata; self }
+    pub fn with_subtree_pool(mut self, enable_subtree_pool: bool) -> Self { self.enable_subtree_pool = enable_subtree_pool; self }
+    pub fn with_pool_min_subtree_nodes(mut self, pool_min_subtree_nodes: usize) -> Self { self.pool_min_subtree_nodes = pool_min_subtree_nodes; self }
+    pub fn with_huffman_pool_refs(mut self, huffman_pool_refs: bool) -> Self { self.huffman_pool_refs = huffman_pool_refs; self }
+    pub fn with_value_dicts(mut self, enable_value_dicts: bool) -> Self { self.enable_value_dicts = enable_value_dicts; self }
+    pub fn with_value_dict_max_values(mut self, value_dict_max_values: usize) -> Self { self.value_dict_max_values = value_dict_max_values; self }
+    pub fn with_redact(mut self, redact: Vec<RedactRule>) -> Self { self.redact = redact; self }
+    pub fn with_path_options(mut self, path_options: Vec<PathOptions>) -> Self { self.path_options = path_options; self }
+    pub fn with_string_subtypes(mut self, string_subtypes: bool) -> Self { self.string_subtypes = string_subtypes; self }
+    pub fn with_timestamp_strings(mut self, timestamp_strings: bool) -> Self { self.timestamp_strings = timestamp_strings; self }
+    pub fn with_typed_arrays(mut self, typed_arrays: bool) -> Self { self.typed_arrays = typed_arrays; self }
+    pub fn with_float_precision(mut self, float_precision: Option<FloatPrecision>) -> Self { self.float_precision = float_precision; self }
+    pub fn with_non_finite_floats(mut self, non_finite_floats: bool) -> Self { self.non_finite_floats = non_finite_floats; self }
+    pub fn with_extensions(mut self, extensions: Vec<Arc<dyn Extension>>) -> Self { self.extensions = extensions; self }
+}
+
+impl CompressOptions {
+    /// 是否输出字符串值池：全局开启，或有路径规则为其开启
+    pub(crate) fn string_pool_enabled(&self) -> bool {
+        self.enable_value_pool || self.path_options.iter().any(|r| r.pool == Some(true))
+    }
+
+    /// 数据区可选写法（字符串标记、定型数组、浮点写法、扩展值）对应的包头标志
+    pub(crate) fn body_flags(&self) -> u64 {
+        let mut fl = 0;
+        if self.string_subtypes { fl |= header::flags::STRING_SUBTYPES; }
+        if self.timestamp_strings { fl |= header::flags::TIMESTAMPS; }
+        if self.typed_arrays { fl |= header::flags::TYPED_ARRAYS; }
+        if self.quantized_floats() { fl |= header::flags::QUANTIZED_FLOATS; }
+        if self.non_finite_floats { fl |= header::flags::NON_FINITE; }
+        if !self.extensions.is_empty() { fl |= header::flags::EXTENSIONS; }
+        fl
+    }
+
+    /// 是否量化浮点数：设置了全局精度，或有路径规则设置精度
+    pub(crate) fn quantized_floats(&self) -> bool {
+        self.float_precision.is_some() || self.path_options.iter().any(|r| r.float_precision.is_some())
+    }
+
+    /// 内联字符串标记；`last` 为本次编码的时间戳差分状态
+    pub(crate) fn string_marks<'c>(&self, last: &'c Cell<i64>) -> StringMarks<'c> {
+        StringMarks { timestamps: self.timestamp_strings.then_some(last), subtypes: self.string_subtypes }
+    }
 }
 
 pub fn compress_with_options(value: &Value, opt: &CompressOptions) -> Result<Vec<u8>, Error> {
-    // 1) 统计键频
-    let freq = dict::collect_keys(value);
-    // 2) 构建 canonical Huffman
-    let codec = HuffmanCodec::from_frequencies(&freq)?;
+    let mut out = Vec::new();
+    compress_append(value, opt, None, &mut SizeHints::default(), &mut out)?;
+    Ok(out)
+}
 
-    // 3) 值池（仅字符串，按需）
-    let (version, pool_len, string_pool) = if opt.enable_value_pool {
-        let pool = collect_string_pool(value, PoolConfig { min_repeats: opt.pool_min_repeats, min_string_len: opt.pool_min_string_len });
-        (header::VERSION_V2, pool.entries.len() as u64, Some(pool))
-    } else {
-        (header::VERSION_V1, 0, None)
-    };
+/// 跨调用保留的状态：统计表借用各次输入文档，无法跨调用保留，只记录上次的表大小供下次预分配；
+/// 键码表连同所依据的键频一并保留，键频相同（如同一结构的响应）时直接复用，不再重建
+#[derive(Debug, Default)]
+pub(crate) struct SizeHints {
+    keys: usize,
+    strings: usize,
+    codec: Option<(HashMap<String, u64>, HuffmanCodec)>,
+    // 单次调用（默认值）不保留键码表，免去复制键名
+    keep_codec: bool,
+}
 
-    // 4) 写包头 + 字典表 + 值池 + 数据
-    let mut writer = BitWriter::new();
-    header::write_header(&mut writer, version, freq.len() as u64, pool_len);
-    dict::write_dictionary(&mut writer, &freq);
-    if let Some(pool) = &string_pool {
-        write_string_pool(&mut writer, pool);
+impl SizeHints {
+    /// 供跨调用复用（`Compressor`）：保留键码表
+    pub(crate) fn reusable() -> Self {
+        Self { keep_codec: true, ..Self::default() }
     }
-    // 统一分派编码（启用/禁用值池皆可）
-    encode_value_dispatch(value, &mut writer, &codec, string_pool.as_ref())?;
 
-    Ok(writer.into_bytes())
+    /// 上次的键码表；仅当键频与本次完全相同时返回
+    fn codec_for(&self, keys: &HashMap<&str, u64>) -> Option<&HuffmanCodec> {
+        let (freqs, codec) = self.codec.as_ref()?;
+        (freqs.len() == keys.len() && keys.iter().all(|(k, n)| freqs.get(*k) == Some(n))).then_some(codec)
+    }
 }
 
-fn encode_value_dispatch(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec, string_pool: Option<&crate::pool::StringPool>) -> Result<(), Error> {
-    match value {
-        Value::String(s) => {
-            if let Some(pool) = string_pool {
-                if let Some(&id) = pool.index.get(s) {
-                    writer.write_bits(tag::STRING as u64, 3);
-                    writer.write_bits(1, 1); // is_pool_ref
-                    varint::write_uleb128(writer, id);
-                    return Ok(());
-                }
+/// 将压缩结果追加到 `out` 末尾；失败时 `out` 保持原长度
+pub(crate) fn compress_append(value: &Value, opt: &CompressOptions, shared: Option<&SharedDictionary>, hints: &mut SizeHints, out: &mut Vec<u8>) -> Result<(), Error> {
+    let value = &*quantize::apply(value, opt)?;
+    let redactions = Redactions::new(value, &opt.redact)?;
+    let redactions = redactions.as_ref();
+    let start = out.len();
+    let mut writer = BitWriter::from_vec(std::mem::take(out));
+    let res = match shared {
+        // 预共享字典未覆盖全部键时退回自包含格式
+        Some(dict) if dict.covers(redact::root(redactions, value), redactions) => write_with_shared(value, opt, dict, redactions, &mut writer),
+        _ => write_self_contained(value, opt, redactions, hints, &mut writer, None),
+    }
+    .map(|_| ());
+    *out = writer.into_bytes();
+    if res.is_err() { out.truncate(start); }
+    res
+}
+
+/// 各区段的结束位置（写入器的绝对位偏移）；值池区含引用码长表
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Layout {
+    pub start: u64,
+    pub header: u64,
+    pub dict: u64,
+    pub pool: u64,
+    pub value_dicts: u64,
+    pub body: u64,
+    /// 字典键数与值池条目数
+    pub keys: u64,
+    pub pool_entries: u64,
+}
+
+/// `redactions` 由 `opt.redact` 对同一文档构建；`stats` 非空时逐值记录数据区编码位数（用于压缩报告）
+pub(crate) fn write_self_contained(value: &Value, opt: &CompressOptions, redactions: Option<&Redactions>, hints: &mut SizeHints, writer: &mut BitWriter, stats: Option<&RefCell<BodyStats>>) -> Result<Layout, Error> {
+    // 1) 单次遍历统计键频与字符串频次（借用 &str），被脱敏的值按输出统计
+    let policy = StringPolicy::new(value, opt, redactions)?;
+    let value = redact::root(redactions, value);
+    let mut analysis = Analysis::with_capacity(opt.string_pool_enabled(), hints.keys, hints.strings).with_policy(policy.as_ref()).with_redactions(redactions).with_extensions(&opt.extensions);
+    if opt.enable_subtree_pool { analysis = analysis.with_subtrees(opt.pool_min_subtree_nodes); }
+    if opt.enable_value_dicts { analysis = analysis.with_key_values(opt.value_dict_max_values); }
+    analysis.add(value);
+    hints.keys = analysis.keys.len();
+    hints.strings = analysis.strings.len();
+
+    // 2) 键码表 -> 值字典 -> 字符串池 -> 重复子树（ID 接在字符串之后）
+    let cached = hints.codec_for(&analysis.keys);
+    let mut sections = Sections::select(opt, &mut analysis, None, cached)?;
+    if opt.enable_subtree_pool {
+        let subtrees = select_subtrees(value, &analysis, &sections.codec, sections.string_pool_len())?;
+        sections.add_subtrees(subtrees);
+    }
+    let layout = write_package(value, opt, &sections, &analysis.keys, policy.as_ref(), redactions, writer, stats)?;
+    if let (true, Cow::Owned(codec)) = (hints.keep_codec, sections.codec) {
+        hints.codec = Some((analysis.keys.iter().map(|(k, &n)| (k.to_string(), n)).collect(), codec));
+    }
+    Ok(layout)
+}
+
+fn write_with_shared(value: &Value, opt: &CompressOptions, dict: &SharedDictionary, redactions: Option<&Redactions>, writer: &mut BitWriter) -> Result<Layout, Error> {
+    let policy = StringPolicy::new(value, opt, redactions)?;
+    let value = redact::root(redactions, value);
+    // 键码表与值池取自字典；值字典按文档统计，随包写入
+    let mut analysis = Analysis::new(false).with_policy(policy.as_ref()).with_redactions(redactions).with_extensions(&opt.extensions);
+    if opt.enable_value_dicts {
+        analysis = analysis.with_key_values(opt.value_dict_max_values);
+        analysis.add(value);
+    }
+    let sections = Sections::select(opt, &mut analysis, Some(dict), None)?;
+    write_package(value, opt, &sections, &analysis.keys, policy.as_ref(), redactions, writer, None)
+}
+
+/// 选择引用码后写出各区段与数据区
+#[allow(clippy::too_many_arguments)]
+fn write_package(value: &Value, opt: &CompressOptions, sections: &Sections, keys: &HashMap<&str, u64>, policy: Option<&StringPolicy>, redactions: Option<&Redactions>, writer: &mut BitWriter, stats: Option<&RefCell<BodyStats>>) -> Result<Layout, Error> {
+    let last = Cell::new(0);
+    let mut body = BodyCtx { strings: sections.strings.as_deref(), subtrees: sections.subtrees.as_ref(), codes: None, values: sections.values.as_ref(), policy, redactions, marks: opt.string_marks(&last), typed_arrays: opt.typed_arrays, quantized_floats: opt.quantized_floats(), extensions: &opt.extensions, stats };
+    let ref_codes = if opt.huffman_pool_refs { body.ref_codes(value, sections.pool_len())? } else { None };
+    body.codes = ref_codes.as_ref();
+    let mut layout = sections.write(writer, opt, keys, ref_codes.as_ref())?;
+    encode_value_dispatch(value, writer, &sections.codec, &body)?;
+    layout.body = writer.bit_position();
+    Ok(layout)
+}
+
+/// 数据区之前的各区段：键码表、值字典与值池由统计结果选出，再写出包头至值字典区。
+/// 编码 `Value` 与 `PackWriter` 的事件日志共用此流程，二者只在统计来源与数据区的遍历上不同
+pub(crate) struct Sections<'p> {
+    pub codec: Cow<'p, HuffmanCodec>,
+    pub strings: Option<Cow<'p, StringPool>>,
+    pub subtrees: Option<SubtreePool<'p>>,
+    pub values: Option<ValueDicts>,
+    shared: Option<&'p SharedDictionary>,
+}
+
+impl<'p> Sections<'p> {
+    /// 键码表 -> 值字典 -> 字符串池；使用预共享字典时键码表与值池取自字典。
+    /// 值字典覆盖的出现次数从 `analysis.strings` 中扣除，余下即为各字符串在数据区的引用次数。
+    /// 未选出任何池条目时不写值池区，数据区字符串也不带 is_pool_ref 位。
+    /// `cached` 为按相同键频构建的键码表（如 `Compressor` 上次调用所建），给出时不再重建
+    pub(crate) fn select(opt: &CompressOptions, analysis: &mut Analysis, shared: Option<&'p SharedDictionary>, cached: Option<&'p HuffmanCodec>) -> Result<Self, Error> {
+        // 有路径规则时最小长度已在统计时逐位置判断
+        let config = PoolConfig { min_repeats: opt.pool_min_repeats, min_string_len: if analysis.policy().is_some() { 0 } else { opt.pool_min_string_len } };
+        let pick = |strings: &HashMap<&str, u32>| Some(select_pool(strings, config)).filter(|p| !p.entries.is_empty()).map(Cow::Owned);
+        let (codec, mut strings) = match shared {
+            Some(dict) => (Cow::Borrowed(dict.codec()), (opt.string_pool_enabled() && !dict.pool().is_empty()).then(|| Cow::Borrowed(dict.string_pool()))),
+            None => {
+                let codec = match cached {
+                    Some(codec) => Cow::Borrowed(codec),
+                    None => Cow::Owned(HuffmanCodec::from_frequencies(&analysis.keys)?),
+                };
+                (codec, if opt.string_pool_enabled() { pick(&analysis.strings) } else { None })
             }
-            // 非引用路径（与原逻辑相同，但需要写 is_pool_ref=0 于 v2）
-            if string_pool.is_some() {
-                writer.write_bits(tag::STRING as u64, 3);
-                writer.write_bits(0, 1);
-                let bytes = s.as_bytes();
-                varint::write_uleb128(writer, bytes.len() as u64);
-                for &b in bytes { writer.write_byte(b); }
-                return Ok(());
+        };
+        let values = if opt.enable_value_dicts {
+            select_value_dicts(&analysis.key_values, &mut analysis.strings, &codec, strings.is_some())?
+        } else {
+            None
+        };
+        // 值字典扣除了部分出现次数，按余下的次数重选
+        if shared.is_none() && values.is_some() && strings.is_some() {
+            strings = pick(&analysis.strings);
+        }
+        Ok(Self { codec, strings, subtrees: None, values, shared })
+    }
+
+    pub(crate) fn string_pool_len(&self) -> u64 {
+        self.strings.as_ref().map_or(0, |p| p.entries.len() as u64)
+    }
+
+    pub(crate) fn pool_len(&self) -> u64 {
+        self.string_pool_len() + self.subtrees.as_ref().map_or(0, |p| p.entries.len() as u64)
+    }
+
+    /// 子树引用依赖值池区：未启用字符串池时使用空字符串池
+    pub(crate) fn add_subtrees(&mut self, subtrees: SubtreePool<'p>) {
+        if subtrees.entries.is_empty() { return; }
+        self.strings.get_or_insert_with(|| Cow::Owned(StringPool::from_entries(Vec::new())));
+        self.subtrees = Some(subtrees);
+    }
+
+    /// 包头 + 字典表（或字典指纹）+ 值池 + [引用码长表] + [值字典区]；返回各区段的结束位置
+    pub(crate) fn write(&self, writer: &mut BitWriter, opt: &CompressOptions, keys: &HashMap<&str, u64>, codes: Option<&RefCodec>) -> Result<Layout, Error> {
+        let mut flags = opt.body_flags();
+        if self.shared.is_some() { flags |= header::flags::SHARED_DICT; }
+        if self.subtrees.is_some() { flags |= header::flags::SUBTREE_POOL; }
+        if codes.is_some() { flags |= header::flags::POOL_REF_CODES; }
+        if self.values.is_some() { flags |= header::flags::VALUE_DICTS; }
+        let dict_len = self.shared.map_or(keys.len(), |dict| dict.keys().len()) as u64;
+        let mut layout = Layout { start: writer.bit_position(), keys: dict_len, pool_entries: self.pool_len(), ..Default::default() };
+        let hdr = package_header(opt, dict_len, self.pool_len(), self.strings.is_some(), flags);
+        header::write_package_header(writer, &hdr);
+        layout.header = writer.bit_position();
+        match self.shared {
+            // 预共享字典：以 32 位指纹替代字典区与值池区
+            Some(dict) => writer.write_bits(dict.fingerprint() as u64, 32),
+            None => dict::write_dictionary(writer, keys),
+        }
+        layout.dict = writer.bit_position();
+        if self.shared.is_none() {
+            if let Some(pool) = &self.strings { write_string_pool(writer, pool); }
+            // 池内子树按原样写入，不含引用
+            if let Some(pool) = &self.subtrees {
+                for entry in &pool.entries { encode_value(entry, writer, &self.codec)?; }
             }
-            // v1：保持原逻辑（无额外 is_pool_ref）
-            writer.write_bits(tag::STRING as u64, 3);
-            let bytes = s.as_bytes();
-            varint::write_uleb128(writer, bytes.len() as u64);
-            for &b in bytes { writer.write_byte(b); }
+        }
+        if let Some(codes) = codes { write_ref_lengths(writer, codes); }
+        layout.pool = writer.bit_position();
+        if let Some(values) = &self.values { values.write(writer, &self.codec)?; }
+        layout.value_dicts = writer.bit_position();
+        Ok(layout)
+    }
+}
+
+/// 挑选逐键值字典，并从字符串频次中扣除其覆盖的出现次数；无可建表的键时返回 None
+pub(crate) fn select_value_dicts<'a>(key_values: &HashMap<&'a str, HashMap<&'a str, u32>>, strings: &mut HashMap<&'a str, u32>, codec: &HuffmanCodec, pooled: bool) -> Result<Option<ValueDicts>, Error> {
+    let values = ValueDicts::select(key_values, codec, pooled)?;
+    for key in values.keys() {
+        for (s, f) in &key_values[key] {
+            // 路径规则可能使部分出现不计入值池统计
+            if let Some(count) = strings.get_mut(s) { *count = count.saturating_sub(*f); }
+        }
+    }
+    Ok(Some(values).filter(|v| !v.is_empty()))
+}
+
+/// 包头：仅在需要可选段时升级到 v3，否则保持 v1/v2 输出不变
+pub(crate) fn package_header(opt: &CompressOptions, dict_len: u64, pool_len: u64, pooled: bool, extra_flags: u64) -> PackageHeader {
+    let version = if pooled { header::VERSION_V2 } else { header::VERSION_V1 };
+    let mut hdr = PackageHeader { version, flags: 0, dict_len, pool_len, metadata: Metadata::new() };
+    let mut fl = extra_flags;
+    if !opt.metadata.is_empty() {
+        fl |= header::flags::METADATA;
+        hdr.metadata = opt.metadata.clone();
+    }
+    if fl != 0 {
+        hdr.version = header::VERSION_V3;
+        hdr.flags = fl | if pooled { header::flags::VALUE_POOL } else { 0 };
+    }
+    hdr
+}
+
+/// POOL_REF_CODES：按 ID 顺序写入每个池条目的引用码长（0 表示未被引用）
+pub(crate) fn write_ref_lengths(writer: &mut BitWriter, codes: &RefCodec) {
+    for &len in codes.lengths() { varint::write_uleb128(writer, len as u64); }
+}
+
+/// 数据区编码上下文：字符串池、子树池、（可选）引用 ID 的 Huffman 码、逐键值字典、路径规则与脱敏规则
+#[derive(Clone, Copy)]
+struct BodyCtx<'p, 'v> {
+    strings: Option<&'p StringPool>,
+    subtrees: Option<&'p SubtreePool<'v>>,
+    codes: Option<&'p RefCodec>,
+    values: Option<&'p ValueDicts>,
+    policy: Option<&'p StringPolicy<'p>>,
+    redactions: Option<&'p Redactions>,
+    marks: StringMarks<'p>,
+    typed_arrays: bool,
+    quantized_floats: bool,
+    extensions: &'p [Arc<dyn Extension>],
+    stats: Option<&'p RefCell<BodyStats>>,
+}
+
+impl BodyCtx<'_, '_> {
+    /// 对象成员的直接字符串值所用的值字典
+    fn value_table(&self, key: &str, value: &Value) -> Option<&HuffmanCodec> {
+        if !value.is_string() { return None; }
+        self.values.and_then(|v| v.get(key))
+    }
+
+    /// EXTENSIONS：由首个接受该值的编解码器写为扩展值（null 标签 + 扩展标记）；无编解码器接受时返回 false
+    fn write_extension(&self, value: &Value, writer: &mut BitWriter) -> bool {
+        let Some((id, payload)) = self.extensions.iter().find_map(|ext| Some((ext.id(), ext.encode(value)?))) else { return false };
+        writer.write_bits(tag::NULL as u64, 3);
+        extension::write(writer, id, &payload);
+        true
+    }
+
+    /// 是否有编解码器接受该值
+    fn captures(&self, value: &Value) -> bool {
+        self.extensions.iter().any(|ext| ext.encode(value).is_some())
+    }
+
+    /// 该值在数据区被替换为引用时的池 ID
+    fn ref_id(&self, value: &Value) -> Option<u64> {
+        match value {
+            Value::String(s) => self.strings.and_then(|p| p.index.get(s).copied()).filter(|_| self.policy.is_none_or(|p| p.poolable(value, s))),
+            Value::Array(_) | Value::Object(_) => self.subtrees.and_then(|p| p.refs.get(&(value as *const Value)).copied()),
+            _ => None,
+        }
+    }
+
+    /// 统计各 ID 的引用次数并构建 Huffman 码；仅当总位数少于 ULEB128 写法时返回
+    fn ref_codes(&self, root: &Value, pool_len: u64) -> Result<Option<RefCodec>, Error> {
+        if pool_len == 0 { return Ok(None); }
+        let mut counts = vec![0u64; pool_len as usize];
+        self.count_refs(root, &mut counts);
+        choose_ref_codes(&counts)
+    }
+
+    fn count_refs(&self, value: &Value, counts: &mut [u64]) {
+        if self.captures(value) { return; }
+        if let Some(id) = self.ref_id(value) {
+            counts[id as usize] += 1;
+            return;
+        }
+        match value {
+            Value::Array(a) => for x in redact::items(self.redactions, a) { self.count_refs(x, counts); },
+            Value::Object(m) => for (k, v) in redact::members(self.redactions, m) {
+                // 走值字典的字符串不产生池引用
+                if self.value_table(k, v).is_none() { self.count_refs(v, counts); }
+            },
+            _ => {}
+        }
+    }
+}
+
+/// 由各池 ID 的引用次数构建 Huffman 码；仅当总位数（含码长表）少于 ULEB128 写法时返回
+pub(crate) fn choose_ref_codes(counts: &[u64]) -> Result<Option<RefCodec>, Error> {
+    if counts.is_empty() { return Ok(None); }
+    let codes = RefCodec::from_frequencies(counts)?;
+    let (mut uleb_bits, mut huff_bits) = (0u64, 0u64);
+    for (id, (&n, &len)) in counts.iter().zip(codes.lengths()).enumerate() {
+        uleb_bits += n * 8 * varint::uleb128_len(id as u64) as u64;
+        huff_bits += n * len as u64 + 8 * varint::uleb128_len(len as u64) as u64;
+    }
+    Ok((huff_bits < uleb_bits).then_some(codes))
+}
+
+/// 池引用 ID：canonical 码字（POOL_REF_CODES）或 ULEB128
+pub(crate) fn write_pool_ref(writer: &mut BitWriter, id: u64, codes: Option<&RefCodec>) -> Result<(), Error> {
+    match codes {
+        Some(codes) => codes.write(id, writer),
+        None => { varint::write_uleb128(writer, id); Ok(()) }
+    }
+}
+
+fn encode_value_dispatch(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec, pool: &BodyCtx) -> Result<(), Error> {
+    let start = writer.bit_position();
+    // 扩展值优先于值池引用：统计时已跳过被编解码器接受的值
+    let extension = pool.write_extension(value, writer);
+    let pooled = !extension && pool.ref_id(value).is_some();
+    if !extension { encode_body_value(value, writer, huffman, pool)?; }
+    let Some(stats) = pool.stats else { return Ok(()) };
+    if pooled || extension || !(value.is_array() || value.is_object()) {
+        stats.borrow_mut().value(value, pooled, writer.bit_position() - start);
+    }
+    Ok(())
+}
+
+fn encode_body_value(value: &Value, writer: &mut BitWriter, huffman: &HuffmanCodec, pool: &BodyCtx) -> Result<(), Error> {
+    if let Some(id) = pool.ref_id(value) {
+        // is_pool_ref=1：string 于 VALUE_POOL 下、object/array 于 SUBTREE_POOL 下
+        let t = match value { Value::String(_) => tag::STRING, Value::Array(_) => tag::ARRAY, _ => tag::OBJECT };
+        writer.write_bits(t as u64, 3);
+        writer.write_bits(1, 1);
+        return write_pool_ref(writer, id, pool.codes);
+    }
+    match value {
+        Value::String(s) => {
+            // 非引用路径：启用值池时写 is_pool_ref=0；v1 无此位
+            write_string(writer, s, pool.strings.is_some(), pool.marks);
             Ok(())
         }
         Value::Array(a) => {
             writer.write_bits(tag::ARRAY as u64, 3);
-            varint::write_uleb128(writer, a.len() as u64);
-            for x in a { encode_value_dispatch(x, writer, huffman, string_pool)?; }
+            if pool.subtrees.is_some() { writer.write_bits(0, 1); }
+            let items = redact::items(pool.redactions, a);
+            let kind = if pool.typed_arrays { Kind::of(items.clone().map(Elem::of)) } else { None };
+            if pool.typed_arrays { typed_array::write_marker(writer, kind); }
+            varint::write_uleb128(writer, items.clone().count() as u64);
+            match kind {
+                Some(kind) => for x in items.filter_map(Elem::of) {
+                    kind.write(writer, x);
+                    if let Some(stats) = pool.stats { stats.borrow_mut().elem(x, kind.bits()); }
+                },
+                None => for x in items { encode_value_dispatch(x, writer, huffman, pool)?; },
+            }
             Ok(())
         }
         Value::Object(m) => {
             writer.write_bits(tag::OBJECT as u64, 3);
-            varint::write_uleb128(writer, m.len() as u64);
-            for (k, v) in m {
+            if pool.subtrees.is_some() { writer.write_bits(0, 1); }
+            varint::write_uleb128(writer, redact::members(pool.redactions, m).count() as u64);
+            for (k, v) in redact::members(pool.redactions, m) {
+                let start = writer.bit_position();
                 huffman.write_key_code(k, writer)?;
-                encode_value_dispatch(v, writer, huffman, string_pool)?;
+                if let Some(stats) = pool.stats { stats.borrow_mut().key(writer.bit_position() - start); }
+                if let (Some(table), Value::String(s)) = (pool.value_table(k, v), v) && !pool.captures(v) {
+                    // VALUE_DICTS：标签后直接写该键值表的码字
+                    let start = writer.bit_position();
+                    writer.write_bits(tag::STRING as u64, 3);
+                    table.write_key_code(s, writer)?;
+                    if let Some(stats) = pool.stats { stats.borrow_mut().value(v, false, writer.bit_position() - start); }
+                    continue;
+                }
+                encode_value_dispatch(v, writer, huffman, pool)?;
             }
             Ok(())
         }
+        Value::Null if !pool.extensions.is_empty() => {
+            // 扩展标记 0：普通 null
+            writer.write_bits(tag::NULL as u64, 3);
+            writer.write_bits(0, 1);
+            Ok(())
+        }
+        Value::Number(n) if pool.quantized_floats && n.is_f64() => {
+            writer.write_bits(tag::FLOAT as u64, 3);
+            quantize::write_float(writer, n.as_f64().ok_or(Error::IllegalFloat)?);
+            Ok(())
+        }
         _ => encode_value(value, writer, huffman),
     }
-}
\ No newline at end of file
+}
@@ -2,37 +2,186 @@ use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
 pub enum Error {
-    #[error("base64 解码失败: {0}")]
+    #[error("base64 decode failed: {0}")]
     Base64Decode(#[from] base64::DecodeError),
 
-    #[error("UTF-8 错误: {0}")]
+    #[error("invalid UTF-8: {0}")]
     Utf8(#[from] std::string::FromUtf8Error),
 
-    #[error("非法浮点数 (NaN/Inf) 不被支持")] 
+    #[error("illegal float (NaN/Inf) is not supported")]
     IllegalFloat,
 
-    #[error("位流越界")] 
+    #[error("bitstream out of bounds")]
     BitstreamOutOfBounds,
 
-    #[error("Varint 溢出或截断")] 
+    #[error("varint overflow or truncated")]
     VarintError,
 
-    #[error("魔数不匹配")] 
+    #[error("bad magic")]
     BadMagic,
 
-    #[error("版本不支持")] 
+    #[error("unsupported version")]
     BadVersion,
 
-    #[error("Huffman 构建/解码错误")] 
+    #[error("unknown header flags: {0:#x}")]
+    UnknownFlags(u64),
+
+    #[error("huffman build/decode error")]
     HuffmanError,
 
-    #[error("值池缺失或未启用")] 
+    #[error("value pool missing or not enabled")]
     PoolMissing,
 
-    #[error("值池引用 ID 越界")] 
+    #[error("value pool reference id out of range")]
     PoolIdOutOfRange,
 
-    #[error("未实现: {0}")] 
+    #[error("invalid type tag: {0:#05b}")]
+    InvalidTag(u8),
+
+    #[error("truncated data")]
+    TruncatedData,
+
+    #[error("trailing data after body at byte {offset}")]
+    TrailingData { offset: u64 },
+
+    #[error("payload requires a shared dictionary (fingerprint {0:#010x})")]
+    SharedDictionaryRequired(u32),
+
+    #[error("shared dictionary mismatch: expected fingerprint {expected:#010x}, found {found:#010x}")]
+    SharedDictionaryMismatch { expected: u32, found: u32 },
+
+    /// 解码错误的位置信息：出错位偏移与正在解码的 JSON 路径。
+    /// 0.2.0 起所有解码错误都包装为此变体，按错误本体匹配请用 `kind()`
+    #[error("{source} (at bit {offset}{})", path_suffix(.path))]
+    Located {
+        offset: u64,
+        /// 数据区内为 JSON Pointer（根为空串）；值池条目以 `pool[ID]` 开头
+        path: String,
+        source: Box<Error>,
+    },
+
+    /// 投影、脱敏或路径选项的路径不是合法的 JSON Pointer（可含 `*`/`**` 通配段）
+    #[error("invalid path pattern: {0:?}")]
+    InvalidPattern(String),
+
+    /// 时间戳字段越界（年份超出 0000–9999、小数或偏移不合法）
+    #[error("timestamp field out of range")]
+    InvalidTimestamp,
+
+    /// 定型数组的类型标记为保留值
+    #[error("reserved typed array kind {0}")]
+    InvalidTypedArray(u8),
+
+    /// 量化浮点数的十进制指数越界（或还原结果溢出为 ±Inf）
+    #[error("quantized float exponent out of range")]
+    InvalidQuantizedFloat,
+
+    /// 扩展类型未注册编解码器（且未选择跳过）
+    #[error("unknown extension type {0}")]
+    UnknownExtension(u64),
+
+    /// 扩展值的载荷无法由编解码器还原
+    #[error("malformed payload for extension type {0}")]
+    InvalidExtension(u64),
+
+    /// PackWriter 调用顺序不合法（如对象外写键、容器未闭合即 finish），或设置了其不支持的选项
+    #[error("invalid writer state: {0}")]
+    WriterState(&'static str),
+
+    /// 已不再产生；保留以兼容既有的匹配分支
+    #[deprecated(note = "no longer returned; reserved typed array kinds report InvalidTypedArray")]
+    #[error("unimplemented: {0}")]
     Unimplemented(&'static str),
 }
 
+fn path_suffix(path: &str) -> String {
+    if path.is_empty() { String::new() } else { format!(", path {path}") }
+}
+
+impl Error {
+    /// 去掉位置信息后的错误本体
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::Located { source, .. } => source.kind(),
+            e => e,
+        }
+    }
+
+    /// 稳定的机器可读错误码
+    pub fn code(&self) -> &'static str {
+        match self.kind() {
+            Error::Base64Decode(_) => "base64_decode",
+            Error::Utf8(_) => "invalid_utf8",
+            Error::IllegalFloat => "illegal_float",
+            Error::BitstreamOutOfBounds => "bitstream_out_of_bounds",
+            Error::VarintError => "varint_error",
+            Error::BadMagic => "bad_magic",
+            Error::BadVersion => "bad_version",
+            Error::UnknownFlags(_) => "unknown_flags",
+            Error::HuffmanError => "huffman_error",
+            Error::PoolMissing => "pool_missing",
+            Error::PoolIdOutOfRange => "pool_id_out_of_range",
+            Error::InvalidTag(_) => "invalid_tag",
+            Error::TruncatedData => "truncated_data",
+            Error::TrailingData { .. } => "trailing_data",
+            Error::SharedDictionaryRequired(_) => "shared_dictionary_required",
+            Error::SharedDictionaryMismatch { .. } => "shared_dictionary_mismatch",
+            Error::InvalidPattern(_) => "invalid_pattern",
+            Error::InvalidTimestamp => "invalid_timestamp",
+            Error::InvalidTypedArray(_) => "invalid_typed_array",
+            Error::InvalidQuantizedFloat => "invalid_quantized_float",
+            Error::UnknownExtension(_) => "unknown_extension",
+            Error::InvalidExtension(_) => "invalid_extension",
+            Error::WriterState(_) => "writer_state",
+            #[allow(deprecated)]
+            Error::Unimplemented(_) => "unimplemented",
+            Error::Located { .. } => unreachable!("kind() strips location"),
+        }
+    }
+
+    /// 出错位置（位偏移），仅解码错误携带
+    pub fn bit_offset(&self) -> Option<u64> {
+        match self {
+            Error::Located { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// 出错时正在解码的路径，仅解码错误携带
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::Located { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// 取出错误本体（去掉位置信息）
+    pub(crate) fn into_kind(self) -> Error {
+        match self {
+            Error::Located { source, .. } => source.into_kind(),
+            e => e,
+        }
+    }
+
+    /// 附加位偏移；已带位置的错误保持最内层位置。读越界在解码语境下即数据截断
+    pub(crate) fn at(self, offset: u64) -> Error {
+        match self {
+            e @ Error::Located { .. } => e,
+            Error::BitstreamOutOfBounds => Error::Located { offset, path: String::new(), source: Box::new(Error::TruncatedData) },
+            e => Error::Located { offset, path: String::new(), source: Box::new(e) },
+        }
+    }
+
+    /// 由内向外逐层在路径前补一个片段
+    pub(crate) fn within(mut self, segment: &str) -> Error {
+        if let Error::Located { path, .. } = &mut self {
+            path.insert_str(0, segment);
+        }
+        self
+    }
+}
+
+/// JSON Pointer 片段：`/` 前缀并转义 `~` 与 `/`
+pub(crate) fn pointer_segment(key: &str) -> String {
+    format!("/{}", key.replace('~', "~0").replace('/', "~1"))
+}
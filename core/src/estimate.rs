@@ -0,0 +1,74 @@
+use std::fmt::{self, Write};
+
+use serde_json::Value;
+
+use crate::{
+    bitstream::BitWriter,
+    encode::{self, CompressOptions, Layout, SizeHints},
+    quantize,
+    redact::Redactions,
+    Error,
+};
+
+/// 压缩大小估算结果：各区段的精确位数，以及原 JSON 紧凑文本的字节数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SizeBreakdown {
+    pub header_bits: u64,
+    pub dict_bits: u64,
+    /// 值池区（含引用码长表）
+    pub pool_bits: u64,
+    pub value_dicts_bits: u64,
+    pub body_bits: u64,
+    /// 紧凑 JSON 文本（同 `serde_json::to_string`）的字节数
+    pub json_bytes: usize,
+}
+
+impl SizeBreakdown {
+    pub fn total_bits(&self) -> u64 {
+        self.header_bits + self.dict_bits + self.pool_bits + self.value_dicts_bits + self.body_bits
+    }
+
+    /// 压缩后的字节数（末尾补齐到整字节），与 `compress_to_bytes` 的输出长度一致
+    pub fn total_bytes(&self) -> usize { self.total_bits().div_ceil(8) as usize }
+
+    /// 压缩后字节数 / JSON 文本字节数；JSON 为空时返回 1.0
+    pub fn ratio(&self) -> f64 {
+        if self.json_bytes == 0 { return 1.0; }
+        self.total_bytes() as f64 / self.json_bytes as f64
+    }
+}
+
+/// 估算压缩大小：与编码器走同一流程，但写入器只计数、不保留输出；`json_bytes` 按调用方的原文档计
+pub fn estimate_size(value: &Value, opts: &CompressOptions) -> Result<SizeBreakdown, Error> {
+    let quantized = quantize::apply(value, opts)?;
+    let redactions = Redactions::new(&quantized, &opts.redact)?;
+    let mut writer = BitWriter::counting();
+    let layout = encode::write_self_contained(&quantized, opts, redactions.as_ref(), &mut SizeHints::default(), &mut writer, None)?;
+    Ok(SizeBreakdown::from_layout(&layout, value))
+}
+
+impl SizeBreakdown {
+    pub(crate) fn from_layout(layout: &Layout, value: &Value) -> Self {
+        let mut counter = ByteCounter(0);
+        // Display 输出即紧凑 JSON 文本；计数器不会写入失败
+        write!(counter, "{value}").expect("counting JSON text cannot fail");
+        Self {
+            header_bits: layout.header - layout.start,
+            dict_bits: layout.dict - layout.header,
+            pool_bits: layout.pool - layout.dict,
+            value_dicts_bits: layout.value_dicts - layout.pool,
+            body_bits: layout.body - layout.value_dicts,
+            json_bytes: counter.0,
+        }
+    }
+}
+
+// 只统计字节数的 fmt::Write
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    header::PackageHeader,
+    varint, Error,
+};
+
+/// 扩展类型编解码器：把应用约定的 JSON 表示（如 `{"$date": ...}`、Base64 二进制串）
+/// 写为紧凑的扩展值。须满足 `decode(encode(v)?) == v`，否则解码结果与原文档不同
+pub trait Extension: fmt::Debug + Send + Sync {
+    /// 扩展类型 ID，同一组编解码器内唯一
+    fn id(&self) -> u64;
+
+    /// 值属于此扩展类型时返回载荷字节；返回 None 则按普通 JSON 编码
+    fn encode(&self, value: &Value) -> Option<Vec<u8>>;
+
+    /// 由载荷还原值；载荷不合法时可返回 `Error::InvalidExtension`
+    fn decode(&self, payload: &[u8]) -> Result<Value, Error>;
+}
+
+/// 解码时遇到未注册的扩展类型的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownExtension {
+    /// 报 `Error::UnknownExtension`
+    #[default]
+    Error,
+    /// 跳过载荷，解码为 null
+    Null,
+}
+
+/// EXTENSIONS 下数据区 null 标签后 1 位：`0` 为 null，`1` 接类型 ID + 载荷字节数（均为 ULEB128）+ 载荷
+pub(crate) fn write(writer: &mut BitWriter, id: u64, payload: &[u8]) {
+    writer.write_bits(1, 1);
+    varint::write_uleb128(writer, id);
+    varint::write_uleb128(writer, payload.len() as u64);
+    for &b in payload { writer.write_byte(b); }
+}
+
+/// 读取扩展值的类型 ID 与载荷长度（标记位之后）
+pub(crate) fn read_head(reader: &mut BitReader) -> Result<(u64, usize), Error> {
+    let id = varint::read_uleb128(reader)?;
+    let len = usize::try_from(varint::read_uleb128(reader)?).map_err(|_| Error::BitstreamOutOfBounds)?;
+    if len > reader.remaining_bits() / 8 { return Err(Error::BitstreamOutOfBounds); }
+    Ok((id, len))
+}
+
+/// 跳过扩展值（标记位之后），返回其类型 ID 与载荷长度
+pub(crate) fn skip(reader: &mut BitReader) -> Result<(u64, usize), Error> {
+    let (id, len) = read_head(reader)?;
+    reader.skip_bits(len * 8)?;
+    Ok((id, len))
+}
+
+/// 数据区解码用的编解码器集合；未置位 EXTENSIONS 的包为 None
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Codecs<'a> {
+    pub registry: &'a [Arc<dyn Extension>],
+    pub unknown: UnknownExtension,
+}
+
+impl<'a> Codecs<'a> {
+    pub(crate) fn of(hdr: &PackageHeader, registry: &'a [Arc<dyn Extension>], unknown: UnknownExtension) -> Option<Self> {
+        hdr.has_extensions().then_some(Self { registry, unknown })
+    }
+
+    /// 解码扩展值载荷（类型 ID 与载荷长度已由 `read_head` 读出）；未注册且按 `UnknownExtension::Null` 处理时返回 None
+    pub(crate) fn decode(&self, reader: &mut BitReader, id: u64, len: usize) -> Result<Option<Value>, Error> {
+        let Some(codec) = self.registry.iter().find(|c| c.id() == id) else {
+            reader.skip_bits(len * 8)?;
+            return match self.unknown {
+                UnknownExtension::Error => Err(Error::UnknownExtension(id)),
+                UnknownExtension::Null => Ok(None),
+            };
+        };
+        let payload = (0..len).map(|_| reader.read_byte()).collect::<Result<Vec<u8>, Error>>()?;
+        codec.decode(&payload).map(Some)
+    }
+}
@@ -1,19 +1,100 @@
+use std::collections::BTreeMap;
+
 use crate::{bitstream::{BitReader, BitWriter}, varint, Error};
 
 pub const MAGIC: [u8; 4] = *b"JCPR"; // 0x4A 0x43 0x50 0x52
 pub const VERSION_V1: u8 = 0x01; // 无值池
 pub const VERSION_V2: u8 = 0x02; // 启用值池（字符串池）
+pub const VERSION_V3: u8 = 0x03; // 扩展头：FLAGS 决定启用的可选段
+
+/// V3 头部 FLAGS 位定义（ULEB128 写入）
+pub mod flags {
+    /// 存在值池区，数据区 string 带 is_pool_ref 位（等价于 v2）
+    pub const VALUE_POOL: u64 = 1 << 0;
+    /// 头部末尾带用户元数据段
+    pub const METADATA: u64 = 1 << 1;
+    /// 使用预共享字典：字典区（及值池区）不写入，改为 32 位字典指纹
+    pub const SHARED_DICT: u64 = 1 << 2;
+    /// 值池可含对象/数组，数据区对象与数组标签后带 is_pool_ref 位
+    pub const SUBTREE_POOL: u64 = 1 << 3;
+    /// 值池引用 ID 以 canonical Huffman 码写入；值池区（或字典指纹）后附各 ID 的码长表
+    pub const POOL_REF_CODES: u64 = 1 << 4;
+    /// 数据区前带逐键值字典区；有值字典的键，其直接字符串值写为该表的 Huffman 码
+    pub const VALUE_DICTS: u64 = 1 << 5;
+    /// 数据区内联字符串（值池条目除外）在 is_pool_ref 位后带子类型标记
+    pub const STRING_SUBTYPES: u64 = 1 << 6;
+    /// 数据区内联字符串（值池条目除外）在 is_pool_ref 位后带 1 位时间戳标记；时间戳秒数按文档顺序差分
+    pub const TIMESTAMPS: u64 = 1 << 7;
+    /// 数据区数组在 is_pool_ref 位后带类型标记；定型数组的元素按定宽块写入，无逐元素标签
+    pub const TYPED_ARRAYS: u64 = 1 << 8;
+    /// 有损：浮点数已按精度量化；数据区 float 标签后带 1 位十进制标记
+    pub const QUANTIZED_FLOATS: u64 = 1 << 9;
+    /// 数据区浮点数可为 NaN/±Inf（按 IEEE 754 位模式原样写入）
+    pub const NON_FINITE: u64 = 1 << 10;
+    /// 数据区 null 标签后带 1 位扩展标记；置位时为应用注册的扩展类型值
+    pub const EXTENSIONS: u64 = 1 << 11;
+
+    /// 当前实现可识别的全部标志位
+    pub const KNOWN: u64 = VALUE_POOL | METADATA | SHARED_DICT | SUBTREE_POOL | POOL_REF_CODES | VALUE_DICTS | STRING_SUBTYPES | TIMESTAMPS | TYPED_ARRAYS | QUANTIZED_FLOATS | NON_FINITE | EXTENSIONS;
+}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// 用户元数据：键值对，按键排序写入以保证确定性
+pub type Metadata = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PackageHeader {
     pub version: u8,
+    pub flags: u64,
     pub dict_len: u64,
     pub pool_len: u64,
+    pub metadata: Metadata,
 }
 
 impl PackageHeader {
     pub fn new(dict_len: u64, pool_len: u64) -> Self {
-        Self { version: VERSION_V1, dict_len, pool_len }
+        Self { version: VERSION_V1, flags: 0, dict_len, pool_len, metadata: Metadata::new() }
+    }
+
+    /// 数据区是否带值池（v2，或 v3 且置位 VALUE_POOL）
+    pub fn has_pool(&self) -> bool {
+        match self.version {
+            VERSION_V2 => true,
+            VERSION_V3 => self.flags & flags::VALUE_POOL != 0,
+            _ => false,
+        }
+    }
+
+    /// 数据区对象/数组是否带 is_pool_ref 位
+    pub fn has_subtree_refs(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::SUBTREE_POOL != 0
+    }
+
+    /// 数据区内联字符串是否带子类型标记
+    pub fn has_string_subtypes(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::STRING_SUBTYPES != 0
+    }
+
+    pub fn has_timestamps(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::TIMESTAMPS != 0
+    }
+
+    pub fn has_typed_arrays(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::TYPED_ARRAYS != 0
+    }
+
+    /// 有损压缩包：浮点数已量化，解码值可能与原文档不同
+    pub fn has_quantized_floats(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::QUANTIZED_FLOATS != 0
+    }
+
+    /// 数据区是否可含非有限浮点数
+    pub fn has_non_finite(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::NON_FINITE != 0
+    }
+
+    /// 数据区是否可含扩展类型值
+    pub fn has_extensions(&self) -> bool {
+        self.version == VERSION_V3 && self.flags & flags::EXTENSIONS != 0
     }
 }
 
@@ -28,16 +109,63 @@ pub fn write_header(writer: &mut BitWriter, version: u8, dict_len: u64, pool_len
     varint::write_uleb128(writer, pool_len);
 }
 
+/// 写入完整包头；v1/v2 与 `write_header` 一致，v3 额外写入 FLAGS 与可选段
+/// 格式（v3）：MAGIC + VERSION + FLAGS(uleb128) + DICT_LEN + POOL_LEN + [METADATA]
+pub fn write_package_header(writer: &mut BitWriter, hdr: &PackageHeader) {
+    if hdr.version != VERSION_V3 {
+        write_header(writer, hdr.version, hdr.dict_len, hdr.pool_len);
+        return;
+    }
+    for b in MAGIC { writer.write_byte(b); }
+    writer.write_byte(hdr.version);
+    varint::write_uleb128(writer, hdr.flags);
+    varint::write_uleb128(writer, hdr.dict_len);
+    varint::write_uleb128(writer, hdr.pool_len);
+    if hdr.flags & flags::METADATA != 0 {
+        // METADATA：COUNT(uleb128) + 对每项 [KEY_LEN][KEY_UTF8][VAL_LEN][VAL_UTF8]
+        varint::write_uleb128(writer, hdr.metadata.len() as u64);
+        for (k, v) in &hdr.metadata {
+            write_str(writer, k);
+            write_str(writer, v);
+        }
+    }
+}
+
 pub fn read_header(reader: &mut BitReader) -> Result<PackageHeader, Error> {
     // MAGIC
     let mut m = [0u8; 4];
-    for i in 0..4 { m[i] = reader.read_byte()?; }
+    for b in m.iter_mut() { *b = reader.read_byte()?; }
     if m != MAGIC { return Err(Error::BadMagic); }
     // VERSION
     let ver = reader.read_byte()?;
-    if ver != VERSION_V1 && ver != VERSION_V2 { return Err(Error::BadVersion); }
+    if ver != VERSION_V1 && ver != VERSION_V2 && ver != VERSION_V3 { return Err(Error::BadVersion); }
+    // FLAGS（仅 v3）
+    let fl = if ver == VERSION_V3 { varint::read_uleb128(reader)? } else { 0 };
+    if fl & !flags::KNOWN != 0 { return Err(Error::UnknownFlags(fl & !flags::KNOWN)); }
     // DICT_LEN & POOL_LEN
     let dict_len = varint::read_uleb128(reader)?;
     let pool_len = varint::read_uleb128(reader)?;
-    Ok(PackageHeader { version: ver, dict_len, pool_len })
-}
\ No newline at end of file
+    // METADATA
+    let mut metadata = Metadata::new();
+    if fl & flags::METADATA != 0 {
+        let count = varint::read_uleb128(reader)?;
+        for _ in 0..count {
+            let k = read_str(reader)?;
+            let v = read_str(reader)?;
+            metadata.insert(k, v);
+        }
+    }
+    Ok(PackageHeader { version: ver, flags: fl, dict_len, pool_len, metadata })
+}
+
+fn write_str(writer: &mut BitWriter, s: &str) {
+    varint::write_uleb128(writer, s.len() as u64);
+    for &b in s.as_bytes() { writer.write_byte(b); }
+}
+
+fn read_str(reader: &mut BitReader) -> Result<String, Error> {
+    let len = varint::read_uleb128(reader)? as usize;
+    let mut bytes = Vec::with_capacity(len.min(reader.remaining_bits() / 8));
+    for _ in 0..len { bytes.push(reader.read_byte()?); }
+    Ok(String::from_utf8(bytes)?)
+}
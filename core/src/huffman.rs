@@ -17,13 +17,13 @@ enum Node {
 }
 
 impl HuffmanCodec {
-    pub fn from_frequencies(freq_map: &HashMap<String, u64>) -> Result<Self, Error> {
+    pub fn from_frequencies<K: AsRef<str>>(freq_map: &HashMap<K, u64>) -> Result<Self, Error> {
         // 收集符号并排序（字典序）确保确定性
-        let mut symbols: Vec<(String, u64)> = freq_map
+        let mut symbols: Vec<(&str, u64)> = freq_map
             .iter()
-            .map(|(k, &f)| (k.clone(), f))
+            .map(|(k, &f)| (k.as_ref(), f))
             .collect();
-        symbols.sort_by(|a, b| a.0.cmp(&b.0));
+        symbols.sort_by(|a, b| a.0.cmp(b.0));
 
         if symbols.is_empty() {
             // 空字典：允许构建一个空的解码器（解码时会失败）
@@ -36,7 +36,7 @@ impl HuffmanCodec {
 
         // 特殊情况：只有一个符号，分配长度1的码字 "0"
         if symbols.len() == 1 {
-            let key = symbols[0].0.clone();
+            let key = symbols[0].0.to_string();
             let mut enc_map = HashMap::new();
             // LSB-first: 单比特0
             enc_map.insert(key.clone(), (0, 1));
@@ -49,13 +49,14 @@ impl HuffmanCodec {
         }
 
         // 1) 通过普通 Huffman 构建 code lengths（叶子深度）
-        let code_lengths = build_code_lengths(&symbols);
+        let freqs: Vec<u64> = symbols.iter().map(|(_, f)| *f).collect();
+        let code_lengths = build_code_lengths(&freqs);
 
         // 2) Canonical 编码：按 (len, key lex) 排序，生成 MSB-first 码字
         let mut by_len: Vec<(usize, &str)> = symbols
             .iter()
             .enumerate()
-            .map(|(i, (k, _))| (code_lengths[i], k.as_str()))
+            .map(|(i, (k, _))| (code_lengths[i], *k))
             .collect();
         by_len.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(b.1)));
 
@@ -102,11 +103,16 @@ impl HuffmanCodec {
     }
 
     pub fn decode_key(&self, reader: &mut BitReader) -> Result<String, Error> {
+        self.decode_key_ref(reader).map(str::to_string)
+    }
+
+    /// 同 `decode_key`，借用码表中的键名而不复制
+    pub(crate) fn decode_key_ref(&self, reader: &mut BitReader) -> Result<&str, Error> {
         // 逐位读取并下行
         let mut node = self.root.as_ref();
         loop {
             match node {
-                Node::Leaf(key) => return Ok(key.clone()),
+                Node::Leaf(key) => return Ok(key),
                 Node::Internal { left, right } => {
                     let bit = reader.read_bits(1)? as u8;
                     node = if bit == 0 { left.as_ref() } else { right.as_ref() };
@@ -118,6 +124,92 @@ impl HuffmanCodec {
     pub fn try_get_code(&self, key: &str) -> Option<(u64, u8)> { self.enc_map.get(key).copied() }
 }
 
+/// 值池引用 ID 的 canonical Huffman 码：符号即 ID，码字按 (码长, ID) 顺序分配。
+/// 只需传输每个 ID 的码长（0 表示未被引用）即可在解码端重建。
+#[derive(Debug, Clone)]
+pub(crate) struct RefCodec {
+    lengths: Vec<u8>,
+    // id -> LSB-first 码字
+    codes: Vec<u64>,
+    // 解码表：各码长的码字数，以及按 (码长, ID) 排列的符号
+    bl_count: Vec<u32>,
+    symbols: Vec<u64>,
+}
+
+impl RefCodec {
+    /// 由各 ID 的引用次数构建；次数为 0 的 ID 不分配码字
+    pub fn from_frequencies(freqs: &[u64]) -> Result<Self, Error> {
+        let used: Vec<usize> = (0..freqs.len()).filter(|&i| freqs[i] > 0).collect();
+        let mut lengths = vec![0u8; freqs.len()];
+        if used.len() == 1 {
+            lengths[used[0]] = 1;
+        } else if used.len() > 1 {
+            let used_freqs: Vec<u64> = used.iter().map(|&i| freqs[i]).collect();
+            for (&i, len) in used.iter().zip(build_code_lengths(&used_freqs)) {
+                lengths[i] = u8::try_from(len).map_err(|_| Error::HuffmanError)?;
+            }
+        }
+        Self::from_lengths(lengths)
+    }
+
+    /// 码长表中读出的一项
+    pub(crate) fn code_len(len: u64) -> Result<u8, Error> {
+        u8::try_from(len).map_err(|_| Error::HuffmanError)
+    }
+
+    /// 由码长表重建；码长超过 63 或码字超额（违反 Kraft 不等式）时报错
+    pub fn from_lengths(lengths: Vec<u8>) -> Result<Self, Error> {
+        let max_len = lengths.iter().copied().max().unwrap_or(0) as usize;
+        if max_len > 63 { return Err(Error::HuffmanError); }
+        let kraft: u128 = lengths.iter().filter(|&&l| l > 0).map(|&l| 1u128 << (max_len - l as usize)).sum();
+        if kraft > 1u128 << max_len { return Err(Error::HuffmanError); }
+        let mut bl_count = vec![0u32; max_len + 1];
+        for &l in &lengths { if l > 0 { bl_count[l as usize] += 1; } }
+
+        let mut next_code = vec![0u64; max_len + 1];
+        let mut code = 0u64;
+        for bits in 1..=max_len {
+            code = (code + bl_count[bits - 1] as u64) << 1;
+            next_code[bits] = code;
+        }
+
+        let mut symbols: Vec<u64> = (0..lengths.len() as u64).filter(|&i| lengths[i as usize] > 0).collect();
+        symbols.sort_by_key(|&i| lengths[i as usize]);
+        let mut codes = vec![0u64; lengths.len()];
+        for &id in &symbols {
+            let len = lengths[id as usize] as usize;
+            codes[id as usize] = reverse_low_bits(next_code[len], len as u8);
+            next_code[len] += 1;
+        }
+        Ok(Self { lengths, codes, bl_count, symbols })
+    }
+
+    pub fn lengths(&self) -> &[u8] { &self.lengths }
+
+    /// 写入某个 ID 的码字；未分配码字的 ID 报错
+    pub fn write(&self, id: u64, writer: &mut BitWriter) -> Result<(), Error> {
+        let len = self.lengths.get(id as usize).copied().filter(|&l| l > 0).ok_or(Error::PoolIdOutOfRange)?;
+        writer.write_bits(self.codes[id as usize], len as u32);
+        Ok(())
+    }
+
+    /// 逐位读取（MSB-first 码字）并按 canonical 规则定位符号
+    pub fn decode(&self, reader: &mut BitReader) -> Result<u64, Error> {
+        let (mut code, mut first, mut index) = (0u64, 0u64, 0u64);
+        for len in 1..self.bl_count.len() {
+            code |= reader.read_bits(1)?;
+            let count = self.bl_count[len] as u64;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(Error::HuffmanError)
+    }
+}
+
 fn reverse_low_bits(mut v: u64, bits: u8) -> u64 {
     let mut r = 0u64;
     for _ in 0..bits {
@@ -141,7 +233,7 @@ enum TreeNode {
     Internal { left: Box<TreeNode>, right: Box<TreeNode> },
 }
 
-fn build_code_lengths(symbols: &[(String, u64)]) -> Vec<usize> {
+fn build_code_lengths(freqs: &[u64]) -> Vec<usize> {
     use std::cmp::Ordering;
     use std::collections::BinaryHeap;
 
@@ -161,7 +253,7 @@ fn build_code_lengths(symbols: &[(String, u64)]) -> Vec<usize> {
     }
 
     let mut heap: BinaryHeap<OrdNode> = BinaryHeap::new();
-    for (i, (_k, f)) in symbols.iter().enumerate() {
+    for (i, f) in freqs.iter().enumerate() {
         heap.push(OrdNode(HeapNode { freq: *f, min_sym_idx: i, node: Box::new(TreeNode::Leaf { sym_idx: i }) }));
     }
 
@@ -183,7 +275,7 @@ fn build_code_lengths(symbols: &[(String, u64)]) -> Vec<usize> {
 
     let root = heap.pop().unwrap().0.node;
     // 计算叶子深度
-    let mut code_lengths = vec![0usize; symbols.len()];
+    let mut code_lengths = vec![0usize; freqs.len()];
     fn walk(node: &TreeNode, depth: usize, lens: &mut [usize]) {
         match node {
             TreeNode::Leaf { sym_idx } => lens[*sym_idx] = depth.max(1),
@@ -210,7 +302,7 @@ fn insert_codeword(root: &mut Node, key: &str, code_msb: u32, len: u8) -> Result
                         // ok
                     } else if let Node::Leaf(_) = left.as_ref() {
                         // 将叶子展开为内部节点
-                        *left = Box::new(Node::Internal { left: Box::new(Node::Leaf(String::new())), right: Box::new(Node::Leaf(String::new())) });
+                        **left = Node::Internal { left: Box::new(Node::Leaf(String::new())), right: Box::new(Node::Leaf(String::new())) };
                     }
                     node = left.as_mut();
                 } else {
@@ -219,7 +311,7 @@ fn insert_codeword(root: &mut Node, key: &str, code_msb: u32, len: u8) -> Result
                     } else if matches!(right.as_ref(), Node::Internal { .. }) {
                         // ok
                     } else if let Node::Leaf(_) = right.as_ref() {
-                        *right = Box::new(Node::Internal { left: Box::new(Node::Leaf(String::new())), right: Box::new(Node::Leaf(String::new())) });
+                        **right = Node::Internal { left: Box::new(Node::Leaf(String::new())), right: Box::new(Node::Leaf(String::new())) };
                     }
                     node = right.as_mut();
                 }
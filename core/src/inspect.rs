@@ -0,0 +1,116 @@
+use std::cell::Cell;
+
+use serde_json::Value;
+
+use crate::{
+    bitstream::BitReader,
+    decode::{self, BodyCtx, PoolRefs},
+    extension::{Codecs, UnknownExtension},
+    dict,
+    header::{self, Metadata},
+    huffman::HuffmanCodec,
+    non_finite::NonFinite,
+    subtype::StringMarks,
+    Error,
+};
+
+/// 单个区段在位流中的位置（位偏移与位长度）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SectionInfo {
+    pub bit_offset: u64,
+    pub bit_len: u64,
+}
+
+/// 字典中的单个键：频次与其 Huffman 码长
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub key: String,
+    pub frequency: u64,
+    pub code_len: u8,
+}
+
+/// 压缩包结构信息，由 `inspect` 生成
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageInfo {
+    pub version: u8,
+    pub flags: u64,
+    pub metadata: Metadata,
+    /// 字典键，按字典序排列（与写入顺序一致）
+    pub keys: Vec<KeyInfo>,
+    /// 值池条目（按 ID 顺序）；未启用值池时为空
+    pub pool: Vec<Value>,
+    pub header: SectionInfo,
+    pub dict: SectionInfo,
+    pub pool_section: SectionInfo,
+    /// 逐键值字典区；未启用时长度为 0
+    pub value_dicts: SectionInfo,
+    pub body: SectionInfo,
+    /// 输入总字节数（含末尾填充及尾随数据）
+    pub total_bytes: usize,
+}
+
+impl PackageInfo {
+    /// 是否启用值池
+    pub fn has_pool(&self) -> bool {
+        self.version == header::VERSION_V2 || (self.version == header::VERSION_V3 && self.flags & header::flags::VALUE_POOL != 0)
+    }
+
+    /// 是否为有损压缩包（浮点数已量化）
+    pub fn is_lossy(&self) -> bool {
+        self.version == header::VERSION_V3 && self.flags & header::flags::QUANTIZED_FLOATS != 0
+    }
+}
+
+/// 解析整个压缩包并报告各区段布局；数据区会被完整走读以确定其长度
+pub fn inspect(bytes: &[u8]) -> Result<PackageInfo, Error> {
+    let mut reader = BitReader::new(bytes);
+
+    let hdr = header::read_header(&mut reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+    let header_end = reader.bit_position();
+    if hdr.flags & header::flags::SHARED_DICT != 0 {
+        // 字典在包外，无法独立解析（需通过 Decompressor 持有字典解码）
+        decode::read_shared_fingerprint(&mut reader, None)?;
+    }
+
+    let freq = dict::read_dictionary(&mut reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+    let codec = HuffmanCodec::from_frequencies(&freq)?;
+    let dict_end = reader.bit_position();
+
+    let pool = decode::read_pool(&mut reader, &hdr, &codec)?;
+    // 引用码长表计入值池区
+    let ids = decode::read_ref_codes(&mut reader, &hdr)?;
+    let pool_end = reader.bit_position();
+
+    let values = decode::read_value_dicts(&mut reader, &hdr, &codec)?;
+    let values_end = reader.bit_position();
+
+    let last = Cell::new(0);
+    // 只需定出数据区边界：跳过而不构建，值池引用不展开，扩展值一律跳过
+    let ctx = BodyCtx { huffman: &codec, refs: PoolRefs::of(&hdr, ids.as_ref()), pool: None, values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(NonFinite::default()), extensions: Codecs::of(&hdr, &[], UnknownExtension::Null) };
+    decode::skip_value(&mut reader, &ctx, None)?;
+    let body_end = reader.bit_position();
+
+    let mut keys: Vec<KeyInfo> = freq
+        .into_iter()
+        .map(|(key, frequency)| {
+            let code_len = codec.try_get_code(&key).map(|(_, l)| l).unwrap_or(0);
+            KeyInfo { key, frequency, code_len }
+        })
+        .collect();
+    keys.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let section = |from: usize, to: usize| SectionInfo { bit_offset: from as u64, bit_len: (to - from) as u64 };
+    Ok(PackageInfo {
+        version: hdr.version,
+        flags: hdr.flags,
+        metadata: hdr.metadata,
+        keys,
+        pool: pool.unwrap_or_default(),
+        header: section(0, header_end),
+        dict: section(header_end, dict_end),
+        pool_section: section(dict_end, pool_end),
+        value_dicts: section(pool_end, values_end),
+        body: section(values_end, body_end),
+        total_bytes: bytes.len(),
+    })
+}
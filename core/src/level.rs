@@ -0,0 +1,84 @@
+use serde_json::Value;
+
+use crate::{encode::{self, CompressOptions}, estimate::estimate_size, Error};
+
+/// 压缩预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Level {
+    /// 仅键字典（v1 格式），分析开销最小
+    Fast,
+    /// 字符串值池 + 引用 ID Huffman 码 + 逐键值字典；是否生效由位代价模型决定
+    #[default]
+    Default,
+    /// 逐一尝试候选配置，保留输出最小者
+    Max,
+}
+
+impl Level {
+    /// 在 `base` 上套用预设的特性开关；元数据、阈值等其余字段沿用 `base`。
+    /// `Max` 返回全部特性开启的配置，实际采用的配置以 `compress_with_level` 的比较结果为准
+    pub fn apply(self, base: &CompressOptions) -> CompressOptions {
+        let (pool, subtrees, extras) = match self {
+            Level::Fast => (false, false, false),
+            Level::Default => (true, false, true),
+            Level::Max => (true, true, true),
+        };
+        with_features(base, pool, subtrees, extras)
+    }
+
+    /// 候选配置（按固定顺序尝试，保证确定性）
+    fn candidates(self, base: &CompressOptions) -> Vec<CompressOptions> {
+        match self {
+            Level::Max => vec![
+                with_features(base, false, false, false),
+                with_features(base, false, false, true),
+                with_features(base, true, false, true),
+                with_features(base, false, true, true),
+                with_features(base, true, true, true),
+            ],
+            level => vec![level.apply(base)],
+        }
+    }
+}
+
+fn with_features(base: &CompressOptions, pool: bool, subtrees: bool, extras: bool) -> CompressOptions {
+    CompressOptions {
+        enable_value_pool: pool,
+        enable_subtree_pool: subtrees,
+        // 以下两项自带收益判断，无收益时不改变输出
+        huffman_pool_refs: extras,
+        enable_value_dicts: extras,
+        ..base.clone()
+    }
+}
+
+/// 按预设压缩的结果及最终采用的选项（以该选项调用 `compress_to_bytes` 可得到相同字节）
+#[derive(Debug, Clone)]
+pub struct Tuned {
+    pub bytes: Vec<u8>,
+    pub options: CompressOptions,
+}
+
+/// 按预设选出压缩选项而不产生输出：`Max` 估算各候选的大小，取最小者，大小相同时取先尝试者
+pub fn tune_options(value: &Value, level: Level, base: &CompressOptions) -> Result<CompressOptions, Error> {
+    let mut candidates = level.candidates(base);
+    let mut best = 0;
+    if candidates.len() > 1 {
+        let mut best_bytes = usize::MAX;
+        for (i, options) in candidates.iter().enumerate() {
+            let size = estimate_size(value, options)?.total_bytes();
+            if size < best_bytes {
+                best = i;
+                best_bytes = size;
+            }
+        }
+    }
+    Ok(candidates.swap_remove(best))
+}
+
+/// 按预设压缩；只编码 `tune_options` 选中的配置
+pub fn compress_with_level(value: &Value, level: Level, base: &CompressOptions) -> Result<Tuned, Error> {
+    let options = tune_options(value, level, base)?;
+    let bytes = encode::compress_with_options(value, &options)?;
+    Ok(Tuned { bytes, options })
+}
@@ -1,4 +1,5 @@
 mod error;
+mod analysis;
 mod base64util;
 mod types;
 mod bitstream;
@@ -9,6 +10,25 @@ mod huffman;
 mod encode;
 mod decode;
 mod pool;
+mod inspect;
+mod disasm;
+mod shared;
+mod compressor;
+mod value_dict;
+mod level;
+mod estimate;
+mod report;
+mod visit;
+mod pack_writer;
+mod project;
+mod redact;
+mod path_options;
+mod subtype;
+mod timestamp;
+mod typed_array;
+mod quantize;
+mod non_finite;
+mod extension;
 
 pub use error::Error;
 pub use base64util::{encode_base64, decode_base64};
@@ -16,15 +36,32 @@ pub use dict::{collect_keys, write_dictionary, read_dictionary};
 pub use huffman::HuffmanCodec;
 pub use pool::{StringPool, PoolConfig};
 pub use encode::CompressOptions;
+pub use decode::DecodeOptions;
+pub use header::Metadata;
+pub use inspect::{inspect, KeyInfo, PackageInfo, SectionInfo};
+pub use disasm::{disassemble, Disassembly, DumpError, DumpLine};
+pub use shared::SharedDictionary;
+pub use compressor::{Compressor, Decompressor};
+pub use level::{compress_with_level, tune_options, Level, Tuned};
+pub use estimate::{estimate_size, SizeBreakdown};
+pub use report::{compress_with_report, CompressionReport, ValueSizes};
+pub use visit::{decode_with_visitor, Visitor};
+pub use pack_writer::PackWriter;
+pub use project::decompress_projected;
+pub use redact::{Redaction, RedactRule};
+pub use path_options::{PathOptions, StringMode};
+pub use quantize::FloatPrecision;
+pub use non_finite::NonFinite;
+pub use extension::{Extension, UnknownExtension};
 
 #[doc(hidden)]
-pub use header::{VERSION_V1, VERSION_V2};
+pub use header::{VERSION_V1, VERSION_V2, VERSION_V3};
 
 #[doc(hidden)]
 pub mod test_expose {
     pub use crate::bitstream::{BitReader, BitWriter};
     pub use crate::varint::{read_sleb128, read_uleb128, write_sleb128, write_uleb128};
-    pub use crate::header::{read_header, write_header, MAGIC, VERSION_V1, VERSION_V2};
+    pub use crate::header::{flags, read_header, write_header, write_package_header, PackageHeader, MAGIC, VERSION_V1, VERSION_V2, VERSION_V3};
     pub use crate::dict::{collect_keys, read_dictionary, write_dictionary};
     pub use crate::types::tag;
     pub use crate::pool::{collect_string_pool, write_string_pool};
@@ -46,6 +83,22 @@ pub fn compress_to_base64(value: &serde_json::Value, opts: &CompressOptions) ->
 /// 从字节数组解压为 JSON
 pub fn decompress_from_bytes(bytes: &[u8]) -> Result<serde_json::Value, Error> { decode::decompress_from_bytes(bytes) }
 
+/// 按解码选项从字节数组解压（如严格模式拒绝尾随数据）
+pub fn decompress_with_options(bytes: &[u8], opts: &DecodeOptions) -> Result<serde_json::Value, Error> {
+    decode::decompress_with_options(bytes, opts)
+}
+
+/// 解压缓冲区开头的一个压缩包，并返回其占用字节数；可用于顺序解析首尾相接的多个压缩包
+pub fn decompress_prefix(bytes: &[u8]) -> Result<(serde_json::Value, usize), Error> {
+    decode::decompress_prefix(bytes)
+}
+
+/// 仅解析包头，读取用户元数据（不解码字典与数据区）
+pub fn read_metadata(bytes: &[u8]) -> Result<Metadata, Error> {
+    let mut reader = bitstream::BitReader::new(bytes);
+    Ok(header::read_header(&mut reader)?.metadata)
+}
+
 /// 从 Base64 字符串解压为 JSON
 pub fn decompress_from_base64(s: &str) -> Result<serde_json::Value, Error> {
     let bytes = decode_base64(s)?;
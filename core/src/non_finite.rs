@@ -0,0 +1,35 @@
+use serde_json::{Number, Value};
+
+/// 解码 NON_FINITE 压缩包时非有限浮点数（NaN/±Inf）在 `Value` 中的表示；
+/// `serde_json::Value` 无法容纳这些值，`Visitor::value_float` 仍收到原始 f64
+#[derive(Debug, Clone, Copy, Default)]
+pub enum NonFinite {
+    /// 解码为 null（与 `JSON.stringify` 一致）
+    Null,
+    /// 解码为字符串 `"NaN"`、`"Infinity"`、`"-Infinity"`
+    #[default]
+    Sentinel,
+    /// 由调用方将原始值映射为任意 `Value`
+    Custom(fn(f64) -> Value),
+}
+
+impl NonFinite {
+    /// 浮点数对应的 `Value`：有限值原样，非有限值按本策略表示
+    pub(crate) fn value(self, f: f64) -> Value {
+        if let Some(n) = Number::from_f64(f) { return Value::Number(n); }
+        match self {
+            NonFinite::Null => Value::Null,
+            NonFinite::Sentinel => Value::from(sentinel(f)),
+            NonFinite::Custom(hook) => hook(f),
+        }
+    }
+}
+
+/// 非有限值的 JSON5 写法
+fn sentinel(f: f64) -> &'static str {
+    match f {
+        f if f.is_nan() => "NaN",
+        f if f > 0.0 => "Infinity",
+        _ => "-Infinity",
+    }
+}
@@ -0,0 +1,382 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    analysis::Analysis,
+    bitstream::BitWriter,
+    encode::{self, CompressOptions, Sections},
+    shared::SharedDictionary,
+    typed_array::{self, Elem, Kind},
+    types::tag,
+    varint,
+    Error,
+};
+
+/// 事件日志中的一项；字符串与键名以驻留 ID 记录，容器长度在闭合时回填
+#[derive(Debug, Clone, Copy)]
+enum Event {
+    Object(usize),
+    Array(usize),
+    End,
+    Key(u32),
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(u32),
+}
+
+enum Frame {
+    Array { start: usize, len: usize },
+    /// `members`：(键 ID, 成员在日志中的起点)；`pending` 表示已写键、尚未写值
+    Object { start: usize, members: Vec<(u32, usize)>, pending: bool },
+}
+
+/// 增量写入器：无需先构建 `serde_json::Value`，按事件写入后 `finish` 得到压缩包。
+/// 事件以紧凑日志缓存（重复字符串只存一份），`finish` 时统计键频与字符串频次再统一编码；
+/// 输出与压缩等价的 `Value` 完全相同（对象成员按 `serde_json::Map` 的顺序排列，重复键保留最后一次）。
+/// 脱敏规则、路径选项、浮点精度、扩展编解码器与子树去重需要完整文档，写入根值时即报 `WriterState`
+pub struct PackWriter {
+    opts: CompressOptions,
+    shared: Option<SharedDictionary>,
+    events: Vec<Event>,
+    frames: Vec<Frame>,
+    texts: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, u32>,
+    has_root: bool,
+}
+
+impl PackWriter {
+    pub fn new(opts: CompressOptions) -> Self {
+        Self { opts, shared: None, events: Vec::new(), frames: Vec::new(), texts: Vec::new(), ids: HashMap::new(), has_root: false }
+    }
+
+    /// 绑定预共享字典；文档含字典外的键时自动退回自包含格式（同 `Compressor`）
+    pub fn with_dictionary(opts: CompressOptions, dict: SharedDictionary) -> Self {
+        Self { shared: Some(dict), ..Self::new(opts) }
+    }
+
+    pub fn begin_object(&mut self) -> Result<(), Error> {
+        self.begin_value()?;
+        self.frames.push(Frame::Object { start: self.events.len(), members: Vec::new(), pending: false });
+        self.events.push(Event::Object(0));
+        Ok(())
+    }
+
+    pub fn key(&mut self, key: &str) -> Result<(), Error> {
+        let pos = self.events.len();
+        match self.frames.last() {
+            Some(Frame::Object { pending: false, .. }) => {}
+            Some(Frame::Object { pending: true, .. }) => return Err(Error::WriterState("key without a value")),
+            _ => return Err(Error::WriterState("key outside of an object")),
+        }
+        let id = self.intern(key);
+        if let Some(Frame::Object { members, pending, .. }) = self.frames.last_mut() {
+            members.push((id, pos));
+            *pending = true;
+        }
+        self.events.push(Event::Key(id));
+        Ok(())
+    }
+
+    pub fn end_object(&mut self) -> Result<(), Error> {
+        match self.frames.last() {
+            Some(Frame::Object { pending: false, .. }) => {}
+            Some(Frame::Object { pending: true, .. }) => return Err(Error::WriterState("key without a value")),
+            _ => return Err(Error::WriterState("end_object without a matching begin_object")),
+        }
+        let Some(Frame::Object { start, members, .. }) = self.frames.pop() else { unreachable!() };
+        let len = self.canonicalize(&members);
+        self.events[start] = Event::Object(len);
+        self.events.push(Event::End);
+        Ok(())
+    }
+
+    pub fn begin_array(&mut self) -> Result<(), Error> {
+        self.begin_value()?;
+        self.frames.push(Frame::Array { start: self.events.len(), len: 0 });
+        self.events.push(Event::Array(0));
+        Ok(())
+    }
+
+    pub fn end_array(&mut self) -> Result<(), Error> {
+        let Some(&Frame::Array { start, len }) = self.frames.last() else {
+            return Err(Error::WriterState("end_array without a matching begin_array"));
+        };
+        self.frames.pop();
+        self.events[start] = Event::Array(len);
+        self.events.push(Event::End);
+        Ok(())
+    }
+
+    pub fn null(&mut self) -> Result<(), Error> { self.scalar(Event::Null) }
+
+    pub fn bool(&mut self, value: bool) -> Result<(), Error> { self.scalar(Event::Bool(value)) }
+
+    pub fn int(&mut self, value: i64) -> Result<(), Error> { self.scalar(Event::Int(value)) }
+
+    pub fn uint(&mut self, value: u64) -> Result<(), Error> {
+        // 与 serde_json::Number 一致：i64 范围内的值按有符号整数编码
+        match i64::try_from(value) {
+            Ok(i) => self.int(i),
+            Err(_) => self.scalar(Event::UInt(value)),
+        }
+    }
+
+    /// NaN/±Inf 仅在启用 `non_finite_floats` 时接受
+    pub fn float(&mut self, value: f64) -> Result<(), Error> {
+        if !value.is_finite() && !self.opts.non_finite_floats { return Err(Error::IllegalFloat); }
+        self.scalar(Event::Float(value))
+    }
+
+    pub fn string(&mut self, value: &str) -> Result<(), Error> {
+        self.begin_value()?;
+        let id = self.intern(value);
+        self.events.push(Event::Str(id));
+        Ok(())
+    }
+
+    /// 写入一个完整的值（如已有的子文档）
+    pub fn value(&mut self, value: &Value) -> Result<(), Error> {
+        match value {
+            Value::Null => self.null(),
+            Value::Bool(b) => self.bool(*b),
+            Value::Number(n) => match (n.as_i64(), n.as_u64(), n.as_f64()) {
+                (Some(i), _, _) => self.int(i),
+                (None, Some(u), _) => self.uint(u),
+                (None, None, Some(f)) => self.float(f),
+                _ => Err(Error::IllegalFloat),
+            },
+            Value::String(s) => self.string(s),
+            Value::Array(arr) => {
+                self.begin_array()?;
+                for item in arr { self.value(item)?; }
+                self.end_array()
+            }
+            Value::Object(map) => {
+                self.begin_object()?;
+                for (k, v) in map {
+                    self.key(k)?;
+                    self.value(v)?;
+                }
+                self.end_object()
+            }
+        }
+    }
+
+    /// 结束写入并编码；根值须已完整写入
+    pub fn finish(self) -> Result<Vec<u8>, Error> {
+        if !self.has_root || !self.frames.is_empty() {
+            return Err(Error::WriterState("unfinished document"));
+        }
+        let shared = self.shared.as_ref().filter(|dict| self.covered_by(dict));
+        let mut writer = BitWriter::new();
+        self.write(&mut writer, shared)?;
+        Ok(writer.into_bytes())
+    }
+
+    fn scalar(&mut self, event: Event) -> Result<(), Error> {
+        self.begin_value()?;
+        self.events.push(event);
+        Ok(())
+    }
+
+    /// 校验值的位置并更新所在容器的计数
+    fn begin_value(&mut self) -> Result<(), Error> {
+        match self.frames.last_mut() {
+            None if self.has_root => return Err(Error::WriterState("more than one root value")),
+            None => {
+                if let Some(reason) = unsupported(&self.opts) { return Err(Error::WriterState(reason)); }
+                self.has_root = true;
+            }
+            Some(Frame::Array { len, .. }) => *len += 1,
+            Some(Frame::Object { pending, .. }) => {
+                if !*pending { return Err(Error::WriterState("value without a key")); }
+                *pending = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn intern(&mut self, s: &str) -> u32 {
+        if let Some(&id) = self.ids.get(s) { return id; }
+        let id = self.texts.len() as u32;
+        let text: Arc<str> = Arc::from(s);
+        self.texts.push(text.clone());
+        self.ids.insert(text, id);
+        id
+    }
+
+    fn text(&self, id: u32) -> &str { &self.texts[id as usize] }
+
+    /// 将刚闭合对象的成员重排为 `serde_json::Map` 的迭代顺序并去除重复键，返回成员数
+    fn canonicalize(&mut self, members: &[(u32, usize)]) -> usize {
+        let Some(&(_, first)) = members.first() else { return 0 };
+        let order: Vec<usize> = if map_is_sorted() {
+            let mut idx: Vec<usize> = (0..members.len()).collect();
+            idx.sort_by(|&a, &b| self.text(members[a].0).cmp(self.text(members[b].0)).then(a.cmp(&b)));
+            // 同名键保留最后一次
+            idx.iter()
+                .enumerate()
+                .filter(|&(i, &m)| idx.get(i + 1).is_none_or(|&next| members[next].0 != members[m].0))
+                .map(|(_, &m)| m)
+                .collect()
+        } else {
+            // preserve_order：位置取首次出现，值取最后一次
+            let mut slots: HashMap<u32, usize> = HashMap::new();
+            let mut order: Vec<usize> = Vec::new();
+            for (i, &(key, _)) in members.iter().enumerate() {
+                match slots.get(&key) {
+                    Some(&slot) => order[slot] = i,
+                    None => {
+                        slots.insert(key, order.len());
+                        order.push(i);
+                    }
+                }
+            }
+            order
+        };
+        if order.iter().copied().eq(0..members.len()) { return order.len(); }
+
+        let end = self.events.len();
+        let mut reordered = Vec::with_capacity(end - first);
+        for &i in &order {
+            let to = members.get(i + 1).map_or(end, |m| m.1);
+            reordered.extend_from_slice(&self.events[members[i].1..to]);
+        }
+        self.events.truncate(first);
+        self.events.extend(reordered);
+        order.len()
+    }
+
+    fn covered_by(&self, dict: &SharedDictionary) -> bool {
+        self.events.iter().all(|ev| match *ev {
+            Event::Key(k) => dict.keys().contains_key(self.text(k)),
+            _ => true,
+        })
+    }
+
+    /// 与 `encode` 共用区段的选择与写出（`Sections`），只有统计来源与数据区遍历改为事件日志
+    fn write(&self, writer: &mut BitWriter, shared: Option<&SharedDictionary>) -> Result<(), Error> {
+        let opt = &self.opts;
+        let mut analysis = Analysis::new(opt.string_pool_enabled());
+        if opt.enable_value_dicts { analysis = analysis.with_key_values(opt.value_dict_max_values); }
+        let mut member = None;
+        for ev in &self.events {
+            let key = member.take();
+            match *ev {
+                Event::Key(k) => {
+                    analysis.add_key(self.text(k));
+                    member = Some(k);
+                }
+                Event::Str(s) => analysis.add_string(key.map(|k| self.text(k)), self.text(s)),
+                _ => {}
+            }
+        }
+        let sections = Sections::select(opt, &mut analysis, shared, None)?;
+        let (codec, string_pool, values) = (&*sections.codec, sections.strings.as_deref(), sections.values.as_ref());
+        let ref_codes = if opt.huffman_pool_refs {
+            // 扣除值字典覆盖的出现后，字符串频次即各池条目的引用次数
+            let counts: Vec<u64> = string_pool
+                .map(|p| p.entries.iter().map(|s| analysis.strings.get(s.as_str()).copied().unwrap_or(0) as u64).collect())
+                .unwrap_or_default();
+            encode::choose_ref_codes(&counts)?
+        } else {
+            None
+        };
+        sections.write(writer, opt, &analysis.keys, ref_codes.as_ref())?;
+
+        // 数据区：容器长度已回填，按日志顺序线性写出
+        let last = Cell::new(0);
+        let marks = opt.string_marks(&last);
+        let mut member = None;
+        let mut events = self.events.iter();
+        while let Some(ev) = events.next() {
+            let key = member.take();
+            match *ev {
+                Event::Object(len) => {
+                    writer.write_bits(tag::OBJECT as u64, 3);
+                    varint::write_uleb128(writer, len as u64);
+                }
+                Event::Array(len) => {
+                    writer.write_bits(tag::ARRAY as u64, 3);
+                    if !opt.typed_arrays {
+                        varint::write_uleb128(writer, len as u64);
+                        continue;
+                    }
+                    // 元素均为标量时恰为其后 len 个事件
+                    let items = &events.as_slice()[..len.min(events.len())];
+                    let kind = Kind::of(items.iter().map(|ev| match *ev {
+                        Event::Float(f) => Some(Elem::Float(f)),
+                        Event::Bool(b) => Some(Elem::Bool(b)),
+                        _ => None,
+                    }));
+                    typed_array::write_marker(writer, kind);
+                    varint::write_uleb128(writer, len as u64);
+                    let Some(kind) = kind else { continue };
+                    for ev in events.by_ref().take(len) {
+                        match *ev {
+                            Event::Float(f) => kind.write(writer, Elem::Float(f)),
+                            Event::Bool(b) => kind.write(writer, Elem::Bool(b)),
+                            _ => unreachable!("typed array holds only scalars"),
+                        }
+                    }
+                }
+                Event::End => {}
+                Event::Key(k) => {
+                    codec.write_key_code(self.text(k), writer)?;
+                    member = Some(k);
+                }
+                Event::Null => encode::encode_value(&Value::Null, writer, codec)?,
+                Event::Bool(b) => encode::encode_value(&Value::Bool(b), writer, codec)?,
+                Event::Int(i) => encode::encode_value(&Value::from(i), writer, codec)?,
+                Event::UInt(u) => encode::encode_value(&Value::from(u), writer, codec)?,
+                Event::Float(f) => {
+                    // 非有限值（NON_FINITE）同样按位模式写入
+                    writer.write_bits(tag::FLOAT as u64, 3);
+                    writer.write_bits(f.to_bits(), 64);
+                }
+                Event::Str(s) => {
+                    let text = self.text(s);
+                    if let Some(table) = key.and_then(|k| values?.get(self.text(k))) {
+                        // VALUE_DICTS：标签后直接写该键值表的码字
+                        writer.write_bits(tag::STRING as u64, 3);
+                        table.write_key_code(text, writer)?;
+                    } else if let Some(&id) = string_pool.and_then(|p| p.index.get(text)) {
+                        writer.write_bits(tag::STRING as u64, 3);
+                        writer.write_bits(1, 1);
+                        encode::write_pool_ref(writer, id, ref_codes.as_ref())?;
+                    } else {
+                        encode::write_string(writer, text, string_pool.is_some(), marks);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 需要完整文档才能编码的选项，事件日志无法支持
+fn unsupported(opt: &CompressOptions) -> Option<&'static str> {
+    if !opt.redact.is_empty() { return Some("redact is not supported by PackWriter"); }
+    if !opt.path_options.is_empty() { return Some("path_options is not supported by PackWriter"); }
+    if opt.float_precision.is_some() { return Some("float_precision is not supported by PackWriter"); }
+    if !opt.extensions.is_empty() { return Some("extensions are not supported by PackWriter"); }
+    if opt.enable_subtree_pool { return Some("enable_subtree_pool is not supported by PackWriter"); }
+    None
+}
+
+/// `serde_json::Map` 是否按键排序（未启用 preserve_order 特性时）
+fn map_is_sorted() -> bool {
+    static SORTED: OnceLock<bool> = OnceLock::new();
+    *SORTED.get_or_init(|| {
+        let mut probe = Map::new();
+        probe.insert("b".to_string(), Value::Null);
+        probe.insert("a".to_string(), Value::Null);
+        probe.keys().next().map(String::as_str) == Some("a")
+    })
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    encode::CompressOptions,
+    quantize::FloatPrecision,
+    project::{Patterns, States, Step},
+    redact::{self, Redactions},
+    Error,
+};
+
+/// 按路径覆盖字符串与浮点数的编码选项。`path` 为 JSON Pointer，段可为 `*`（任意一段）或 `**`（任意多段）；
+/// 匹配容器时作用于其内全部字符串（浮点数），更深处匹配的规则优先，同一位置多条规则匹配时取靠前的一条
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathOptions {
+    pub path: String,
+    /// 覆盖 `enable_value_pool`：匹配位置的字符串能否入池与引用池条目
+    pub pool: Option<bool>,
+    /// 覆盖 `pool_min_string_len`
+    pub pool_min_string_len: Option<usize>,
+    /// 字符串写法；None 同 `StringMode::Auto`
+    pub string_mode: Option<StringMode>,
+    /// 覆盖 `float_precision`：匹配位置的浮点数按此精度量化（有损）；None 沿用外层生效的精度
+    pub float_precision: Option<FloatPrecision>,
+}
+
+impl PathOptions {
+    /// 不覆盖任何选项的规则，配合结构体更新语法设置需要的字段
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into(), pool: None, pool_min_string_len: None, string_mode: None, float_precision: None }
+    }
+}
+
+/// 字符串写法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringMode {
+    /// 按全局选项与代价模型选择池引用、值字典或内联
+    #[default]
+    Auto,
+    /// 始终内联原文：不入池、不引用；值字典按键名建表，所在键不再建表
+    Inline,
+}
+
+/// 一次编码内各字符串出现位置（按地址）所适用的规则
+#[derive(Debug)]
+pub(crate) struct StringPolicy<'o> {
+    rules: &'o [PathOptions],
+    enable_pool: bool,
+    min_string_len: usize,
+    matched: HashMap<*const Value, usize>,
+}
+
+impl<'o> StringPolicy<'o> {
+    /// 无路径规则时返回 None（保持全局选项的行为不变）；路径按输入文档中的位置匹配，记录脱敏后的输出值
+    pub(crate) fn new(value: &Value, opt: &'o CompressOptions, redactions: Option<&Redactions>) -> Result<Option<Self>, Error> {
+        if opt.path_options.is_empty() { return Ok(None); }
+        let paths: Vec<&str> = opt.path_options.iter().map(|r| r.path.as_str()).collect();
+        let patterns = Patterns::parse(&paths)?;
+        let mut policy = Self { rules: &opt.path_options, enable_pool: opt.enable_value_pool, min_string_len: opt.pool_min_string_len, matched: HashMap::new() };
+        policy.collect(&patterns, redactions, value, &patterns.start(), None);
+        Ok(Some(policy))
+    }
+
+    fn collect(&mut self, patterns: &Patterns, redactions: Option<&Redactions>, value: &Value, states: &States, inherited: Option<usize>) {
+        let rule = patterns.matched(states).or(inherited);
+        let Some(value) = redact::resolve(redactions, value) else { return };
+        match value {
+            Value::String(_) => if let Some(rule) = rule { self.matched.insert(value as *const Value, rule); },
+            Value::Array(arr) => for (i, item) in arr.iter().enumerate() {
+                self.collect(patterns, redactions, item, &patterns.step(states, Step::Index(i)), rule);
+            },
+            Value::Object(map) => for (k, v) in map {
+                self.collect(patterns, redactions, v, &patterns.step(states, Step::Key(k)), rule);
+            },
+            _ => {}
+        }
+    }
+
+    fn rule(&self, value: &Value) -> Option<&PathOptions> {
+        self.matched.get(&(value as *const Value)).map(|&i| &self.rules[i])
+    }
+
+    /// 该位置的字符串是否参与值池统计与引用
+    pub(crate) fn poolable(&self, value: &Value, s: &str) -> bool {
+        let rule = self.rule(value);
+        if rule.and_then(|r| r.string_mode) == Some(StringMode::Inline) { return false; }
+        let min_len = rule.and_then(|r| r.pool_min_string_len).unwrap_or(self.min_string_len);
+        rule.and_then(|r| r.pool).unwrap_or(self.enable_pool) && s.len() >= min_len
+    }
+
+    /// 该位置的字符串是否强制内联
+    pub(crate) fn inline(&self, value: &Value) -> bool {
+        self.rule(value).and_then(|r| r.string_mode) == Some(StringMode::Inline)
+    }
+}
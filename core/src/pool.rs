@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde_json::Value;
 
-use crate::{bitstream::BitWriter, varint, types::tag};
+use crate::{analysis::Analysis, bitstream::BitWriter, encode::encode_value, huffman::HuffmanCodec, redact, varint, types::tag, Error};
 
 #[derive(Debug, Clone)]
 pub struct StringPool {
@@ -12,44 +12,159 @@ pub struct StringPool {
     pub index: HashMap<String, u64>,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// 值池选择配置：默认仅按位代价模型决定是否入池；阈值为额外下限，0 表示不设下限
+#[derive(Debug, Clone, Copy, Default)]
 pub struct PoolConfig {
+    /// 最小重复次数（重复不足 2 次的字符串本就不会入池）
     pub min_repeats: u32,
+    /// 最小字符串字节长度
     pub min_string_len: usize,
 }
 
-impl Default for PoolConfig {
-    fn default() -> Self { Self { min_repeats: 3, min_string_len: 8 } }
+pub fn collect_string_pool(root: &Value, cfg: PoolConfig) -> StringPool {
+    let mut analysis = Analysis::new(true);
+    analysis.add(root);
+    select_pool(&analysis.strings, cfg)
 }
 
-pub fn collect_string_pool(root: &Value, cfg: PoolConfig) -> StringPool {
-    let mut counter: HashMap<String, u32> = HashMap::new();
-    fn walk(v: &Value, counter: &mut HashMap<String, u32>) {
-        match v {
-            Value::String(s) => {
-                *counter.entry(s.clone()).or_insert(0) += 1;
-            }
-            Value::Array(a) => for x in a { walk(x, counter); },
-            Value::Object(m) => for (_k, x) in m { walk(x, counter); },
-            _ => {}
+/// 字符串入池净收益的估算（位），`id` 为其将获得的池 ID：
+/// 内联 c 次 = c × (3 + 1 + 8 × (uleb(len) + len))；
+/// 入池 = 池条目 (3 + 8 × (uleb(len) + len)) + c 次引用 (3 + 1 + 8 × uleb(id)) + 包头 pool_len 变长整数的增长。
+/// 启发式：不计内联时的时间戳/子类型标记位与紧凑载荷、Huffman 引用码长及子树池 ID 偏移，
+/// 这些取决于最终的池布局，选择阶段无法得知
+pub(crate) fn estimated_pool_savings(len: usize, count: u32, id: u64) -> i64 {
+    let body = 8 * (varint::uleb128_len(len as u64) + len) as i64;
+    let reference = 8 * varint::uleb128_len(id) as i64;
+    let pool_len_growth = 8 * (varint::uleb128_len(id + 1) - varint::uleb128_len(id)) as i64;
+    (count as i64 - 1) * body - count as i64 * reference - 3 - pool_len_growth
+}
+
+/// 按估算的位代价筛选入池字符串并分配 ID（仅入池的字符串会被复制）
+pub(crate) fn select_pool(counts: &HashMap<&str, u32>, cfg: PoolConfig) -> StringPool {
+    // 频次降序（高频者获得更短的 ID），其次字节序升序，确保确定性
+    let mut candidates: Vec<(&str, u32)> = counts
+        .iter()
+        .map(|(&s, &c)| (s, c))
+        .filter(|(s, c)| *c >= cfg.min_repeats.max(2) && s.len() >= cfg.min_string_len)
+        .collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    // 按顺序试分配 ID，只保留估算收益为正的条目；被拒绝的条目不占用 ID
+    let mut entries: Vec<String> = Vec::new();
+    for (s, count) in candidates {
+        if estimated_pool_savings(s.len(), count, entries.len() as u64) > 0 {
+            entries.push(s.to_string());
         }
     }
-    walk(root, &mut counter);
+    StringPool::from_entries(entries)
+}
 
-    // 过滤并排序：频次降序，其次字节序升序，确保确定性
-    let mut candidates: Vec<(String, u32)> = counter
-        .into_iter()
-        .filter(|(s, c)| *c >= cfg.min_repeats && s.len() >= cfg.min_string_len)
-        .collect();
-    candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+impl StringPool {
+    /// 按给定顺序分配 ID；重复项只保留首次出现
+    pub fn from_entries(strings: impl IntoIterator<Item = String>) -> Self {
+        let mut entries: Vec<String> = Vec::new();
+        let mut index: HashMap<String, u64> = HashMap::new();
+        for s in strings {
+            if index.contains_key(&s) { continue; }
+            index.insert(s.clone(), entries.len() as u64);
+            entries.push(s);
+        }
+        StringPool { entries, index }
+    }
+}
 
-    let mut entries: Vec<String> = Vec::with_capacity(candidates.len());
-    let mut index: HashMap<String, u64> = HashMap::with_capacity(candidates.len());
-    for (i, (s, _)) in candidates.into_iter().enumerate() {
-        index.insert(s.clone(), i as u64);
-        entries.push(s);
+/// 编码期的子树池：重复出现的对象/数组只写入值池一次，ID 接在字符串池之后
+#[derive(Debug, Default)]
+pub(crate) struct SubtreePool<'a> {
+    pub entries: Vec<&'a Value>,
+    // 被替换为引用的出现位置（按地址）-> 值池 ID
+    pub refs: HashMap<*const Value, u64>,
+}
+
+/// 子树在文档中的一次出现
+struct Occurrence<'a> {
+    value: &'a Value,
+    // (结构哈希, 组内序号)
+    key: (u64, usize),
+    // 最近的受统计祖先出现（下标）
+    parent: Option<usize>,
+    // 自身及后代中的容器数，即内联时 is_pool_ref=0 的位数
+    containers: usize,
+}
+
+/// 子树入池净收益的估算（位）：池条目按无引用的原样编码写入（`entry_bits`），
+/// 内联一次约为 entry_bits + 容器数（各容器的 is_pool_ref 位）；引用一次 = 3 + 1 + 8 × uleb(id)。
+/// 启发式：不计内联时的字符串池引用、标记位与 Huffman 引用码长
+pub(crate) fn estimated_subtree_savings(entry_bits: u64, containers: usize, count: u32, id: u64) -> i64 {
+    let inline = (entry_bits + containers as u64) as i64;
+    let reference = 4 + 8 * varint::uleb128_len(id) as i64;
+    let pool_len_growth = 8 * (varint::uleb128_len(id + 1) - varint::uleb128_len(id)) as i64;
+    count as i64 * (inline - reference) - entry_bits as i64 - pool_len_growth
+}
+
+/// 选择入池子树：按容器数降序（祖先先于后代）逐个判断，出现次数只计未被已入池祖先覆盖的位置；
+/// 入池者在未被覆盖的位置整体引用，ID 按文档中首次引用的顺序分配
+pub(crate) fn select_subtrees<'a>(root: &'a Value, analysis: &Analysis<'a>, codec: &HuffmanCodec, first_id: u64) -> Result<SubtreePool<'a>, Error> {
+    let mut occurrences = Vec::new();
+    collect_occurrences(root, analysis, None, &mut occurrences);
+    // 各不同子树的出现位置（按文档顺序）
+    let mut groups: HashMap<(u64, usize), Vec<usize>> = HashMap::new();
+    for (i, occ) in occurrences.iter().enumerate() { groups.entry(occ.key).or_default().push(i); }
+    let mut order: Vec<&Vec<usize>> = groups.values().filter(|g| g.len() >= 2).collect();
+    order.sort_by_key(|g| (std::cmp::Reverse(occurrences[g[0]].containers), g[0]));
+
+    let mut selected: HashSet<(u64, usize)> = HashSet::new();
+    for group in order {
+        let first = &occurrences[group[0]];
+        let count = group.iter().filter(|&&i| !covered(&occurrences, i, &selected)).count() as u32;
+        if count < 2 { continue; }
+        let mut writer = BitWriter::counting();
+        encode_value(first.value, &mut writer, codec)?;
+        let id = first_id + selected.len() as u64;
+        if estimated_subtree_savings(writer.bit_position(), first.containers, count, id) > 0 {
+            selected.insert(first.key);
+        }
+    }
+
+    let mut pool = SubtreePool::default();
+    let mut ids: HashMap<(u64, usize), u64> = HashMap::new();
+    for (i, occ) in occurrences.iter().enumerate() {
+        if !selected.contains(&occ.key) || covered(&occurrences, i, &selected) { continue; }
+        let id = *ids.entry(occ.key).or_insert_with(|| {
+            pool.entries.push(occ.value);
+            first_id + pool.entries.len() as u64 - 1
+        });
+        pool.refs.insert(occ.value as *const Value, id);
+    }
+    Ok(pool)
+}
+
+/// 该出现是否位于某个已入池子树之内
+fn covered(occurrences: &[Occurrence], i: usize, selected: &HashSet<(u64, usize)>) -> bool {
+    let mut parent = occurrences[i].parent;
+    while let Some(p) = parent {
+        if selected.contains(&occurrences[p].key) { return true; }
+        parent = occurrences[p].parent;
     }
-    StringPool { entries, index }
+    false
+}
+
+/// 先序收集受统计子树的出现位置；返回 `value` 自身及后代中的容器数
+fn collect_occurrences<'a>(value: &'a Value, analysis: &Analysis<'a>, parent: Option<usize>, out: &mut Vec<Occurrence<'a>>) -> usize {
+    // 扩展值不展开为容器
+    if analysis.captured.contains(&(value as *const Value)) { return 0; }
+    let this = analysis.subtree_hashes.get(&(value as *const Value)).map(|&hash| {
+        let slot = analysis.subtrees[&hash].iter().position(|entry| entry.0 == value).expect("subtree recorded during analysis");
+        out.push(Occurrence { value, key: (hash, slot), parent, containers: 0 });
+        out.len() - 1
+    });
+    let parent = this.or(parent);
+    let containers = match value {
+        Value::Object(map) => 1 + redact::members(analysis.redactions(), map).map(|(_, v)| collect_occurrences(v, analysis, parent, out)).sum::<usize>(),
+        Value::Array(arr) => 1 + redact::items(analysis.redactions(), arr).map(|v| collect_occurrences(v, analysis, parent, out)).sum::<usize>(),
+        _ => 0,
+    };
+    if let Some(i) = this { out[i].containers = containers; }
+    containers
 }
 
 pub fn write_string_pool(writer: &mut BitWriter, pool: &StringPool) {
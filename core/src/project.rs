@@ -0,0 +1,216 @@
+use std::cell::Cell;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    bitstream::BitReader,
+    decode::{self, BodyCtx, DecodeOptions, LazyPool, PoolEntries, PoolRefs},
+    extension::Codecs,
+    dict,
+    error::pointer_segment,
+    header,
+    huffman::HuffmanCodec,
+    shared::SharedDictionary,
+    subtype::StringMarks,
+    typed_array,
+    types::tag,
+    varint,
+    Error,
+};
+
+/// 投影路径的一段：字面键（可同时作数组下标）、`*` 任意一段、`**` 任意多段（含零段）
+#[derive(Debug)]
+enum Segment {
+    Key(String, Option<usize>),
+    Any,
+    AnyDepth,
+}
+
+/// 路径中的当前位置：对象键或数组下标
+#[derive(Clone, Copy)]
+pub(crate) enum Step<'k> {
+    Key(&'k str),
+    Index(usize),
+}
+
+/// 一组路径模式（投影与脱敏规则共用）；匹配状态为 (路径序号, 已匹配段数) 的集合
+pub(crate) struct Patterns(Vec<Vec<Segment>>);
+
+pub(crate) type States = Vec<(usize, usize)>;
+
+impl Patterns {
+    /// 路径为 JSON Pointer（`~0`/`~1` 转义），空串表示整个文档。
+    /// 数字段按 RFC 6901 须为规范下标：`+1`、`01` 等写法报 `InvalidPattern`
+    pub(crate) fn parse(patterns: &[&str]) -> Result<Self, Error> {
+        let mut parsed = Vec::with_capacity(patterns.len());
+        for &p in patterns {
+            if p.is_empty() { parsed.push(Vec::new()); continue; }
+            let invalid = || Error::InvalidPattern(p.to_string());
+            let rest = p.strip_prefix('/').ok_or_else(invalid)?;
+            let segments = rest
+                .split('/')
+                .map(|s| match s {
+                    "*" => Ok(Segment::Any),
+                    "**" => Ok(Segment::AnyDepth),
+                    _ => {
+                        let key = s.replace("~1", "/").replace("~0", "~");
+                        let digits = key.strip_prefix('+').unwrap_or(&key);
+                        let numeric = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+                        if numeric && (digits.len() < key.len() || (key.len() > 1 && key.starts_with('0'))) { return Err(invalid()); }
+                        let index = key.parse().ok();
+                        Ok(Segment::Key(key, index))
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            parsed.push(segments);
+        }
+        Ok(Self(parsed))
+    }
+
+    pub(crate) fn start(&self) -> States {
+        let mut states = Vec::new();
+        for p in 0..self.0.len() { self.add(&mut states, p, 0); }
+        states
+    }
+
+    /// 加入状态及其 `**` 零段闭包
+    fn add(&self, states: &mut States, p: usize, mut i: usize) {
+        loop {
+            if !states.contains(&(p, i)) { states.push((p, i)); }
+            match self.0[p].get(i) {
+                Some(Segment::AnyDepth) => i += 1,
+                _ => return,
+            }
+        }
+    }
+
+    pub(crate) fn step(&self, states: &States, step: Step) -> States {
+        let mut next = Vec::new();
+        for &(p, i) in states {
+            match (&self.0[p].get(i), step) {
+                (Some(Segment::Any), _) => self.add(&mut next, p, i + 1),
+                (Some(Segment::AnyDepth), _) => self.add(&mut next, p, i),
+                (Some(Segment::Key(key, _)), Step::Key(k)) if key == k => self.add(&mut next, p, i + 1),
+                (Some(Segment::Key(_, Some(index))), Step::Index(n)) if *index == n => self.add(&mut next, p, i + 1),
+                _ => {}
+            }
+        }
+        next
+    }
+
+    fn complete(&self, states: &States) -> bool {
+        self.matched(states).is_some()
+    }
+
+    /// 已完整匹配的路径中序号最小者
+    pub(crate) fn matched(&self, states: &States) -> Option<usize> {
+        states.iter().filter(|&&(p, i)| i == self.0[p].len()).map(|&(p, _)| p).min()
+    }
+
+    /// 对已解码的值（如值池条目）做投影
+    fn project_value(&self, value: &Value, states: &States) -> Option<Value> {
+        if states.is_empty() { return None; }
+        if self.complete(states) { return Some(value.clone()); }
+        match value {
+            Value::Array(arr) => {
+                let items: Vec<Value> = arr
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| self.project_value(item, &self.step(states, Step::Index(i))))
+                    .collect();
+                (!items.is_empty()).then_some(Value::Array(items))
+            }
+            Value::Object(map) => {
+                let members: Map<String, Value> = map
+                    .iter()
+                    .filter_map(|(k, v)| Some((k.clone(), self.project_value(v, &self.step(states, Step::Key(k)))?)))
+                    .collect();
+                (!members.is_empty()).then_some(Value::Object(members))
+            }
+            _ => None,
+        }
+    }
+
+    /// 从位流投影一个值：完全匹配的子树完整解码，可能匹配的容器逐层下行，其余直接跳过
+    fn project(&self, reader: &mut BitReader, ctx: &BodyCtx, member: Option<&HuffmanCodec>, states: &States) -> Result<Option<Value>, Error> {
+        if states.is_empty() {
+            decode::skip_value(reader, ctx, member)?;
+            return Ok(None);
+        }
+        if self.complete(states) { return decode::decode_value(reader, ctx, member).map(Some); }
+        let t = reader.clone().read_bits(3).map_err(|e| e.at(reader.bit_position() as u64))? as u8;
+        if t != tag::ARRAY && t != tag::OBJECT {
+            decode::skip_value(reader, ctx, member)?;
+            return Ok(None);
+        }
+        self.project_container(reader, ctx, states).map_err(|e| e.at(reader.bit_position() as u64))
+    }
+
+    fn project_container(&self, reader: &mut BitReader, ctx: &BodyCtx, states: &States) -> Result<Option<Value>, Error> {
+        let t = reader.read_bits(3)? as u8;
+        if ctx.refs.containers && reader.read_bits(1)? == 1 {
+            return Ok(self.project_value(ctx.pool_ref(reader)?, states));
+        }
+        let kind = if t == tag::ARRAY && ctx.typed_arrays { typed_array::read_marker(reader)? } else { None };
+        let count = varint::read_uleb128(reader)?;
+        if t == tag::ARRAY {
+            let mut items = Vec::new();
+            for i in 0..count {
+                let next = self.step(states, Step::Index(i as usize));
+                if let Some(kind) = kind {
+                    // 定型元素均为标量：读出后按完整匹配取舍
+                    let item = kind.read(reader).and_then(|elem| ctx.elem(elem)).map_err(|e| e.within(&format!("/{i}")))?;
+                    items.extend(self.project_value(&item, &next));
+                    continue;
+                }
+                if let Some(item) = self.project(reader, ctx, None, &next).map_err(|e| e.within(&format!("/{i}")))? {
+                    items.push(item);
+                }
+            }
+            return Ok((!items.is_empty()).then_some(Value::Array(items)));
+        }
+        let mut members = Map::new();
+        for _ in 0..count {
+            let key = ctx.huffman.decode_key_ref(reader)?;
+            let table = ctx.values.and_then(|v| v.get(key));
+            let next = self.step(states, Step::Key(key));
+            if let Some(value) = self.project(reader, ctx, table, &next).map_err(|e| e.within(&pointer_segment(key)))? {
+                members.insert(key.to_string(), value);
+            }
+        }
+        Ok((!members.is_empty()).then_some(Value::Object(members)))
+    }
+}
+
+/// 按投影路径解压：只构建匹配的子树，其余部分（含未被引用的值池条目）直接跳过。
+/// 路径为 JSON Pointer，段可为 `*`（任意一段）或 `**`（任意多段）；未匹配任何内容时返回 `null`
+pub fn decompress_projected(bytes: &[u8], patterns: &[&str]) -> Result<Value, Error> {
+    decompress_projected_with(bytes, patterns, &DecodeOptions::default(), None)
+}
+
+/// 只解码与路径匹配的部分；`opts` 提供扩展类型编解码器，`shared` 为接收方持有的预共享字典
+pub(crate) fn decompress_projected_with(bytes: &[u8], patterns: &[&str], opts: &DecodeOptions, shared: Option<&SharedDictionary>) -> Result<Value, Error> {
+    let patterns = Patterns::parse(patterns)?;
+    let mut reader = BitReader::new(bytes);
+    let hdr = header::read_header(&mut reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+    let root = if hdr.flags & header::flags::SHARED_DICT != 0 {
+        let dict = decode::read_shared_fingerprint(&mut reader, shared)?;
+        let ids = decode::read_ref_codes(&mut reader, &hdr)?;
+        let values = decode::read_value_dicts(&mut reader, &hdr, dict.codec())?;
+        let pool = hdr.has_pool().then(|| PoolEntries::Decoded(dict.pool_values()));
+        let last = Cell::new(0);
+        let ctx = BodyCtx { huffman: dict.codec(), refs: PoolRefs::of(&hdr, ids.as_ref()), pool, values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(opts.non_finite), extensions: Codecs::of(&hdr, &opts.extensions, opts.unknown_extensions) };
+        patterns.project(&mut reader, &ctx, None, &patterns.start())?
+    } else {
+        let freq = dict::read_dictionary(&mut reader).map_err(|e| e.at(reader.bit_position() as u64))?;
+        let codec = HuffmanCodec::from_frequencies(&freq)?;
+        // 值池区只记录条目位置，被匹配部分引用时才解码
+        let pool = LazyPool::read(&mut reader, bytes, &hdr, &codec)?;
+        let ids = decode::read_ref_codes(&mut reader, &hdr)?;
+        let values = decode::read_value_dicts(&mut reader, &hdr, &codec)?;
+        let last = Cell::new(0);
+        let ctx = BodyCtx { huffman: &codec, refs: PoolRefs::of(&hdr, ids.as_ref()), pool: pool.as_ref().map(PoolEntries::Lazy), values: values.as_ref(), marks: StringMarks::of(&hdr, &last), typed_arrays: hdr.has_typed_arrays(), quantized_floats: hdr.has_quantized_floats(), non_finite: hdr.has_non_finite().then_some(opts.non_finite), extensions: Codecs::of(&hdr, &opts.extensions, opts.unknown_extensions) };
+        patterns.project(&mut reader, &ctx, None, &patterns.start())?
+    };
+    Ok(root.unwrap_or(Value::Null))
+}
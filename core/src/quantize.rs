@@ -0,0 +1,137 @@
+use std::borrow::Cow;
+
+use serde_json::{Map, Number, Value};
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    encode::CompressOptions,
+    project::{Patterns, States, Step},
+    varint, Error,
+};
+
+/// 有损浮点精度：浮点数先按精度舍入，再以十进制缩放整数写入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatPrecision {
+    /// 保留小数点后 N 位
+    Decimals(u8),
+    /// 保留 N 位有效数字（相对精度约 10^(1-N)）
+    Significant(u8),
+}
+
+impl FloatPrecision {
+    /// 舍入到该精度；结果溢出时保留原值
+    pub(crate) fn quantize(self, f: f64) -> f64 {
+        let exp = match self {
+            FloatPrecision::Decimals(d) => -(d as i32),
+            FloatPrecision::Significant(_) if f == 0.0 => return f,
+            FloatPrecision::Significant(n) => f.abs().log10().floor() as i32 + 1 - n.max(1) as i32,
+        };
+        let m = if exp < 0 { f * pow10(-exp) } else { f / pow10(exp) }.round();
+        let q = from_decimal(m, exp);
+        if q.is_finite() { q } else { f }
+    }
+}
+
+fn pow10(n: i32) -> f64 {
+    10f64.powi(n)
+}
+
+/// `m × 10^exp`；负指数用除法，使 10^22 以内的结果为最近的 f64
+fn from_decimal(m: f64, exp: i32) -> f64 {
+    if exp < 0 { m / pow10(-exp) } else { m * pow10(exp) }
+}
+
+/// 按全局精度与路径规则量化浮点数；无任何精度设置或无匹配时借用原文档。
+/// 路径规则匹配容器时作用于其内全部浮点数，更深处匹配的规则优先；整数不受影响
+pub(crate) fn apply<'v>(value: &'v Value, opt: &CompressOptions) -> Result<Cow<'v, Value>, Error> {
+    if !opt.quantized_floats() { return Ok(Cow::Borrowed(value)); }
+    let paths: Vec<&str> = opt.path_options.iter().map(|r| r.path.as_str()).collect();
+    let patterns = Patterns::parse(&paths)?;
+    let quantizer = Quantizer { opt, patterns };
+    Ok(quantizer.quantize(value, &quantizer.patterns.start(), opt.float_precision))
+}
+
+struct Quantizer<'o> {
+    opt: &'o CompressOptions,
+    patterns: Patterns,
+}
+
+impl Quantizer<'_> {
+    fn quantize<'v>(&self, value: &'v Value, states: &States, inherited: Option<FloatPrecision>) -> Cow<'v, Value> {
+        let precision = match self.patterns.matched(states) {
+            Some(rule) => self.opt.path_options[rule].float_precision.or(inherited),
+            None => inherited,
+        };
+        match value {
+            Value::Number(n) if n.is_f64() => {
+                let (Some(precision), Some(f)) = (precision, n.as_f64()) else { return Cow::Borrowed(value) };
+                let q = precision.quantize(f);
+                if q.to_bits() == f.to_bits() { return Cow::Borrowed(value); }
+                Cow::Owned(Number::from_f64(q).map_or(Value::Null, Value::Number))
+            }
+            Value::Array(arr) => {
+                let items: Vec<_> = arr.iter().enumerate().map(|(i, item)| self.quantize(item, &self.patterns.step(states, Step::Index(i)), precision)).collect();
+                if items.iter().all(|item| matches!(item, Cow::Borrowed(_))) { return Cow::Borrowed(value); }
+                Cow::Owned(Value::Array(items.into_iter().map(Cow::into_owned).collect()))
+            }
+            Value::Object(map) => {
+                let members: Vec<_> = map.iter().map(|(k, v)| (k, self.quantize(v, &self.patterns.step(states, Step::Key(k)), precision))).collect();
+                if members.iter().all(|(_, v)| matches!(v, Cow::Borrowed(_))) { return Cow::Borrowed(value); }
+                let map: Map<String, Value> = members.into_iter().map(|(k, v)| (k.clone(), v.into_owned())).collect();
+                Cow::Owned(Value::Object(map))
+            }
+            _ => Cow::Borrowed(value),
+        }
+    }
+}
+
+/// 可精确还原的十进制写法 (尾数, 指数)：取最短往返表示的各位数字，解码结果须与原值逐位相同
+fn decimal_form(f: f64) -> Option<(i64, i32)> {
+    let text = format!("{f:e}");
+    let (digits, exp) = text.split_once('e')?;
+    let exp: i32 = exp.parse().ok()?;
+    let (sign, digits) = digits.strip_prefix('-').map_or((1, digits), |d| (-1, d));
+    let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+    let m: i64 = format!("{int}{frac}").parse().ok()?;
+    let exp = exp - frac.len() as i32;
+    (from_decimal((sign * m) as f64, exp).to_bits() == f.to_bits()).then_some((sign * m, exp))
+}
+
+/// 符号位 + ULEB128 绝对值
+fn write_signed(writer: &mut BitWriter, n: i64) {
+    writer.write_bits((n < 0) as u64, 1);
+    varint::write_uleb128(writer, n.unsigned_abs());
+}
+
+fn read_signed(reader: &mut BitReader) -> Result<i64, Error> {
+    let negative = reader.read_bits(1)? == 1;
+    let n = i64::try_from(varint::read_uleb128(reader)?).map_err(|_| Error::VarintError)?;
+    Ok(if negative { -n } else { n })
+}
+
+/// QUANTIZED_FLOATS 下数据区浮点数：FLOAT 标签后 1 位，`0` 接 64 位原值，
+/// `1` 接尾数与十进制指数（均为符号位 + ULEB128 绝对值）；取位数较少者
+pub(crate) fn write_float(writer: &mut BitWriter, f: f64) {
+    let signed = |n: i64| 1 + 8 * varint::uleb128_len(n.unsigned_abs()) as u64;
+    match decimal_form(f).filter(|&(m, exp)| signed(m) + signed(exp as i64) < 64) {
+        Some((m, exp)) => {
+            writer.write_bits(1, 1);
+            write_signed(writer, m);
+            write_signed(writer, exp as i64);
+        }
+        None => {
+            writer.write_bits(0, 1);
+            writer.write_bits(f.to_bits(), 64);
+        }
+    }
+}
+
+/// 64 位原值可能非有限，由调用方按包头校验；十进制形式的写入方只产出有限值
+pub(crate) fn read_float(reader: &mut BitReader) -> Result<f64, Error> {
+    if reader.read_bits(1)? == 0 { return Ok(f64::from_bits(reader.read_bits(64)?)); }
+    let m = read_signed(reader)?;
+    let exp = read_signed(reader)?;
+    // 有限 f64 的十进制指数（含 17 位尾数）远小于此界
+    if exp.unsigned_abs() > 400 { return Err(Error::InvalidQuantizedFloat); }
+    Some(from_decimal(m as f64, exp as i32)).filter(|f| f.is_finite()).ok_or(Error::InvalidQuantizedFloat)
+}
@@ -0,0 +1,136 @@
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "redact-hash")]
+use std::fmt::Write;
+
+use serde_json::{Map, Value};
+#[cfg(feature = "redact-hash")]
+use sha2::{Digest, Sha256};
+
+use crate::{
+    project::{Patterns, States, Step},
+    Error,
+};
+
+/// 编码前的脱敏规则：`path` 为 JSON Pointer，段可为 `*`（任意一段）或 `**`（任意多段）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactRule {
+    pub path: String,
+    pub action: Redaction,
+}
+
+/// 匹配值的处理方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Redaction {
+    /// 移除该成员（数组元素则移除该元素）；根值变为 null
+    Drop,
+    /// 替换为 null
+    Null,
+    /// 字符串替换为 `salt + 原值` 的 SHA-256 十六进制摘要；非字符串值替换为 null（需启用 `redact-hash` 特性）
+    #[cfg(feature = "redact-hash")]
+    Hash { salt: String },
+}
+
+impl RedactRule {
+    pub fn drop(path: impl Into<String>) -> Self {
+        Self { path: path.into(), action: Redaction::Drop }
+    }
+
+    pub fn null(path: impl Into<String>) -> Self {
+        Self { path: path.into(), action: Redaction::Null }
+    }
+
+    #[cfg(feature = "redact-hash")]
+    pub fn hash(path: impl Into<String>, salt: impl Into<String>) -> Self {
+        Self { path: path.into(), action: Redaction::Hash { salt: salt.into() } }
+    }
+}
+
+/// 一次编码内命中脱敏规则的位置（按地址）及其输出。编码器与各遍统计（键字典、值池、子树、值字典）
+/// 都经此读取对象成员与数组元素，被移除或替换的原值不会进入输出
+#[derive(Debug)]
+pub(crate) struct Redactions {
+    // 命中位置 -> 替换值；None 表示移除
+    matched: HashMap<*const Value, Option<Value>>,
+    // 其内有命中位置的容器：原样与输出不同，不参与子树去重
+    touched: HashSet<*const Value>,
+}
+
+impl Redactions {
+    /// 无规则时返回 None；多条规则同时匹配时取靠前的一条
+    pub(crate) fn new(value: &Value, rules: &[RedactRule]) -> Result<Option<Self>, Error> {
+        if rules.is_empty() { return Ok(None); }
+        let paths: Vec<&str> = rules.iter().map(|r| r.path.as_str()).collect();
+        let patterns = Patterns::parse(&paths)?;
+        let mut redactions = Self { matched: HashMap::new(), touched: HashSet::new() };
+        redactions.collect(rules, &patterns, value, &patterns.start());
+        // 根值被移除时输出 null
+        if let Some(output @ None) = redactions.matched.get_mut(&(value as *const Value)) { *output = Some(Value::Null); }
+        Ok(Some(redactions))
+    }
+
+    /// 返回该位置或其内是否有规则命中
+    fn collect(&mut self, rules: &[RedactRule], patterns: &Patterns, value: &Value, states: &States) -> bool {
+        if states.is_empty() { return false; }
+        if let Some(rule) = patterns.matched(states) {
+            let output = match &rules[rule].action {
+                Redaction::Drop => None,
+                Redaction::Null => Some(Value::Null),
+                #[cfg(feature = "redact-hash")]
+                Redaction::Hash { salt } => Some(match value {
+                    Value::String(s) => Value::String(digest(salt, s)),
+                    _ => Value::Null,
+                }),
+            };
+            self.matched.insert(value as *const Value, output);
+            return true;
+        }
+        let mut hit = false;
+        match value {
+            Value::Array(arr) => for (i, item) in arr.iter().enumerate() {
+                hit |= self.collect(rules, patterns, item, &patterns.step(states, Step::Index(i)));
+            },
+            Value::Object(map) => for (k, v) in map {
+                hit |= self.collect(rules, patterns, v, &patterns.step(states, Step::Key(k)));
+            },
+            _ => {}
+        }
+        if hit { self.touched.insert(value as *const Value); }
+        hit
+    }
+
+    /// 该容器内是否有位置命中规则
+    pub(crate) fn touches(&self, value: &Value) -> bool {
+        self.touched.contains(&(value as *const Value))
+    }
+}
+
+/// 该位置在输出中的值；None 表示被移除。根值不会被移除
+pub(crate) fn resolve<'a>(redactions: Option<&'a Redactions>, value: &'a Value) -> Option<&'a Value> {
+    match redactions.and_then(|r| r.matched.get(&(value as *const Value))) {
+        Some(output) => output.as_ref(),
+        None => Some(value),
+    }
+}
+
+/// 输出中的根值
+pub(crate) fn root<'a>(redactions: Option<&'a Redactions>, value: &'a Value) -> &'a Value {
+    resolve(redactions, value).expect("a dropped root is replaced with null")
+}
+
+/// 输出中的对象成员（跳过被移除者）
+pub(crate) fn members<'a>(redactions: Option<&'a Redactions>, map: &'a Map<String, Value>) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    map.iter().filter_map(move |(k, v)| Some((k, resolve(redactions, v)?)))
+}
+
+/// 输出中的数组元素（跳过被移除者）
+pub(crate) fn items<'a>(redactions: Option<&'a Redactions>, arr: &'a [Value]) -> impl Iterator<Item = &'a Value> + Clone {
+    arr.iter().filter_map(move |v| resolve(redactions, v))
+}
+
+#[cfg(feature = "redact-hash")]
+fn digest(salt: &str, s: &str) -> String {
+    let hash = Sha256::new().chain_update(salt).chain_update(s).finalize();
+    let mut hex = String::with_capacity(hash.len() * 2);
+    for b in hash { write!(hex, "{b:02x}").expect("writing to a String cannot fail"); }
+    hex
+}
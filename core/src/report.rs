@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::{
+    bitstream::BitWriter,
+    encode::{self, CompressOptions, SizeHints},
+    estimate::{estimate_size, SizeBreakdown},
+    path_options::PathOptions,
+    quantize,
+    redact::Redactions,
+    typed_array::Elem,
+    Error,
+};
+
+/// 某类值在数据区的编码位数分布
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValueSizes {
+    pub count: u64,
+    pub bits: u64,
+    /// 编码位数 -> 出现次数
+    pub by_bits: BTreeMap<u64, u64>,
+}
+
+impl ValueSizes {
+    fn record(&mut self, bits: u64) {
+        self.count += 1;
+        self.bits += bits;
+        *self.by_bits.entry(bits).or_insert(0) += 1;
+    }
+}
+
+/// 压缩报告：各区段位数、键码长、值池命中与各类值的编码大小
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompressionReport {
+    pub sizes: SizeBreakdown,
+    /// 字典中的不同键数
+    pub keys: usize,
+    /// 数据区写入的键码字次数
+    pub key_refs: u64,
+    /// 数据区键码字的平均位数
+    pub avg_key_code_len: f64,
+    pub pool_entries: u64,
+    /// 数据区中的值池引用次数（字符串与子树）
+    pub pool_hits: u64,
+    /// 相比关闭值池（字符串与子树）的同配置输出节省的位数
+    pub pool_bits_saved: i64,
+    /// 以下均只统计数据区；字符串含值池引用与值字典码字
+    pub ints: ValueSizes,
+    pub floats: ValueSizes,
+    pub strings: ValueSizes,
+    /// 含定型数组中的布尔元素（每个 1 位）
+    pub bools: ValueSizes,
+}
+
+/// 数据区编码过程中的逐值统计
+#[derive(Debug, Default)]
+pub(crate) struct BodyStats {
+    key_refs: u64,
+    key_bits: u64,
+    pool_hits: u64,
+    ints: ValueSizes,
+    floats: ValueSizes,
+    strings: ValueSizes,
+    bools: ValueSizes,
+}
+
+impl BodyStats {
+    pub fn key(&mut self, bits: u64) {
+        self.key_refs += 1;
+        self.key_bits += bits;
+    }
+
+    /// 记录一个标量或池引用的编码位数；容器本身不记录
+    pub fn value(&mut self, value: &Value, pooled: bool, bits: u64) {
+        if pooled { self.pool_hits += 1; }
+        match value {
+            Value::String(_) => self.strings.record(bits),
+            Value::Number(n) if n.is_f64() => self.floats.record(bits),
+            Value::Number(_) => self.ints.record(bits),
+            Value::Bool(_) => self.bools.record(bits),
+            _ => {}
+        }
+    }
+
+    /// 记录一个定型数组元素（无标签，按元素类型计入）
+    pub fn elem(&mut self, elem: Elem, bits: u64) {
+        match elem {
+            Elem::Float(_) => self.floats.record(bits),
+            Elem::Bool(_) => self.bools.record(bits),
+        }
+    }
+}
+
+/// 压缩并返回报告；字节与 `compress_to_bytes` 的输出相同
+pub fn compress_with_report(value: &Value, opts: &CompressOptions) -> Result<(Vec<u8>, CompressionReport), Error> {
+    let input = value;
+    let value = &*quantize::apply(value, opts)?;
+    let redactions = Redactions::new(value, &opts.redact)?;
+    let stats = RefCell::new(BodyStats::default());
+    let mut writer = BitWriter::new();
+    let layout = encode::write_self_contained(value, opts, redactions.as_ref(), &mut SizeHints::default(), &mut writer, Some(&stats))?;
+    let bytes = writer.into_bytes();
+    let stats = stats.into_inner();
+
+    // JSON 大小按输入文档计，而非量化后的副本
+    let sizes = SizeBreakdown::from_layout(&layout, input);
+    let pool_bits_saved = if opts.string_pool_enabled() || opts.enable_subtree_pool {
+        // 路径规则中的值池开关一并关闭（量化重复应用结果不变）
+        let path_options = opts.path_options.iter().map(|r| PathOptions { pool: None, ..r.clone() }).collect();
+        let plain = CompressOptions { enable_value_pool: false, enable_subtree_pool: false, path_options, ..opts.clone() };
+        estimate_size(value, &plain)?.total_bits() as i64 - sizes.total_bits() as i64
+    } else {
+        0
+    };
+    let report = CompressionReport {
+        sizes,
+        keys: layout.keys as usize,
+        key_refs: stats.key_refs,
+        avg_key_code_len: if stats.key_refs == 0 { 0.0 } else { stats.key_bits as f64 / stats.key_refs as f64 },
+        pool_entries: layout.pool_entries,
+        pool_hits: stats.pool_hits,
+        pool_bits_saved,
+        ints: stats.ints,
+        floats: stats.floats,
+        strings: stats.strings,
+        bools: stats.bools,
+    };
+    Ok((bytes, report))
+}
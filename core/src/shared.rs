@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+    analysis::Analysis,
+    bitstream::BitWriter,
+    dict,
+    huffman::HuffmanCodec,
+    pool::{self, PoolConfig, StringPool},
+    redact::{self, Redactions},
+    Error,
+};
+
+/// 预共享字典：键频表（及可选字符串池）由收发双方事先约定。
+/// 使用它压缩时，压缩包内不再写字典区与值池区，只写 32 位指纹用于校验。
+#[derive(Debug, Clone)]
+pub struct SharedDictionary {
+    keys: HashMap<String, u64>,
+    pool: StringPool,
+    // 解码侧直接克隆的池值
+    pool_values: Vec<Value>,
+    codec: HuffmanCodec,
+    fingerprint: u32,
+}
+
+impl SharedDictionary {
+    /// 由键频表与池字符串（按 ID 顺序）构建
+    pub fn new(keys: HashMap<String, u64>, pool_strings: Vec<String>) -> Result<Self, Error> {
+        let codec = HuffmanCodec::from_frequencies(&keys)?;
+        let pool = StringPool::from_entries(pool_strings);
+        let pool_values = pool.entries.iter().cloned().map(Value::String).collect();
+
+        // 指纹：对字典区与值池区的规范序列化做 FNV-1a
+        let mut w = BitWriter::new();
+        dict::write_dictionary(&mut w, &keys);
+        pool::write_string_pool(&mut w, &pool);
+        let fingerprint = fnv1a32(&w.into_bytes());
+
+        Ok(Self { keys, pool, pool_values, codec, fingerprint })
+    }
+
+    /// 从样本文档汇总键频；给定 `pool` 时按其阈值跨样本挑选池字符串
+    pub fn from_samples<'a>(samples: impl IntoIterator<Item = &'a Value>, pool: Option<PoolConfig>) -> Result<Self, Error> {
+        let mut analysis = Analysis::new(pool.is_some());
+        for sample in samples { analysis.add(sample); }
+        let keys = analysis.keys.iter().map(|(k, &f)| (k.to_string(), f)).collect();
+        let pool_strings = match pool {
+            Some(cfg) => pool::select_pool(&analysis.strings, cfg).entries,
+            None => Vec::new(),
+        };
+        Self::new(keys, pool_strings)
+    }
+
+    pub fn keys(&self) -> &HashMap<String, u64> { &self.keys }
+
+    pub fn pool(&self) -> &[String] { &self.pool.entries }
+
+    pub fn fingerprint(&self) -> u32 { self.fingerprint }
+
+    pub(crate) fn codec(&self) -> &HuffmanCodec { &self.codec }
+
+    pub(crate) fn string_pool(&self) -> &StringPool { &self.pool }
+
+    pub(crate) fn pool_values(&self) -> &Vec<Value> { &self.pool_values }
+
+    /// 文档（脱敏后）中的所有键是否都在字典内
+    pub(crate) fn covers(&self, value: &Value, redactions: Option<&Redactions>) -> bool {
+        match value {
+            Value::Object(m) => redact::members(redactions, m).all(|(k, v)| self.keys.contains_key(k) && self.covers(v, redactions)),
+            Value::Array(a) => redact::items(redactions, a).all(|v| self.covers(v, redactions)),
+            _ => true,
+        }
+    }
+}
+
+fn fnv1a32(bytes: &[u8]) -> u32 {
+    let mut h: u32 = 0x811c_9dc5;
+    for &b in bytes {
+        h ^= b as u32;
+        h = h.wrapping_mul(0x0100_0193);
+    }
+    h
+}
@@ -0,0 +1,254 @@
+use std::cell::Cell;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    header::PackageHeader,
+    timestamp::Timestamp,
+    varint, Error,
+};
+
+/// 内联字符串在 is_pool_ref 位之后的可选标记（由包头决定；值池条目均不带）
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct StringMarks<'a> {
+    /// TIMESTAMPS：先写 1 位是否为时间戳；单元格保存数据区上一个时间戳的秒数
+    pub timestamps: Option<&'a Cell<i64>>,
+    /// STRING_SUBTYPES：子类型标记
+    pub subtypes: bool,
+}
+
+impl<'a> StringMarks<'a> {
+    /// `last` 为本次编解码的时间戳差分状态，初值 0
+    pub(crate) fn of(hdr: &PackageHeader, last: &'a Cell<i64>) -> Self {
+        Self { timestamps: hdr.has_timestamps().then_some(last), subtypes: hdr.has_string_subtypes() }
+    }
+
+    /// 写入标记与载荷（位于标签与 is_pool_ref 位之后）
+    pub(crate) fn write(self, writer: &mut BitWriter, s: &str) {
+        if let Some(last) = self.timestamps {
+            let ts = Timestamp::parse(s);
+            writer.write_bits(ts.is_some() as u64, 1);
+            if let Some(ts) = ts { return ts.write(writer, last); }
+        }
+        if self.subtypes { return write_subtype(writer, s); }
+        write_plain(writer, s);
+    }
+
+    /// 读取标记与载荷，将还原的字符串字节写入 `buf`（调用方负责 UTF-8 校验）；返回子类型名，原文为 None
+    pub(crate) fn read(self, reader: &mut BitReader, buf: &mut Vec<u8>) -> Result<Option<&'static str>, Error> {
+        buf.clear();
+        if let Some(last) = self.timestamps
+            && reader.read_bits(1)? == 1
+        {
+            Timestamp::read(reader, last)?.format(buf);
+            return Ok(Some("timestamp"));
+        }
+        if self.subtypes { return read_subtype(reader, buf); }
+        read_plain(reader, buf)?;
+        Ok(None)
+    }
+
+    /// 跳过标记与载荷；时间戳仍需读出以维护差分状态
+    pub(crate) fn skip(self, reader: &mut BitReader) -> Result<(), Error> {
+        if let Some(last) = self.timestamps
+            && reader.read_bits(1)? == 1
+        {
+            return Timestamp::read(reader, last).map(|_| ());
+        }
+        if self.subtypes { return skip_subtype(reader); }
+        let len = varint::read_uleb128(reader)?;
+        skip_units(reader, len, 8)
+    }
+}
+
+fn write_plain(writer: &mut BitWriter, s: &str) {
+    varint::write_uleb128(writer, s.len() as u64);
+    for &b in s.as_bytes() { writer.write_byte(b); }
+}
+
+fn read_plain(reader: &mut BitReader, buf: &mut Vec<u8>) -> Result<(), Error> {
+    let len = varint::read_uleb128(reader)? as usize;
+    // 以剩余位数限制预分配，避免截断/损坏数据导致超大分配
+    buf.reserve(len.min(reader.remaining_bits() / 8));
+    for _ in 0..len { buf.push(reader.read_byte()?); }
+    Ok(())
+}
+
+fn skip_units(reader: &mut BitReader, len: u64, unit: u64) -> Result<(), Error> {
+    let bits = len.checked_mul(unit).and_then(|n| usize::try_from(n).ok()).ok_or(Error::BitstreamOutOfBounds)?;
+    reader.skip_bits(bits)
+}
+
+/// STRING_SUBTYPES 下的子类型标记：`0` 为原文，`1` 后接 2 位子类型
+mod kind {
+    /// 规范大小写的 UUID：1 位大小写 + 16 字节
+    pub const UUID: u64 = 0;
+    /// 十六进制串：1 位大小写 + ULEB128 半字节数 + 每位 4 bit
+    pub const HEX: u64 = 1;
+    /// 规范 Base64（标准字母表、带填充）：ULEB128 字节数 + 原始字节
+    pub const BASE64: u64 = 2;
+    /// 规范十进制串（无前导零、无符号，u64 范围内）：ULEB128 数值
+    pub const DECIMAL: u64 = 3;
+}
+
+/// 候选写法；均可由载荷精确还原原字符串
+enum Form {
+    Plain,
+    Uuid { upper: bool, bytes: [u8; 16] },
+    Hex { upper: bool },
+    Base64(Vec<u8>),
+    Decimal(u64),
+}
+
+impl Form {
+    /// 标记与载荷的总位数
+    fn bits(&self, s: &str) -> u64 {
+        let uleb = |n: usize| 8 * varint::uleb128_len(n as u64) as u64;
+        match self {
+            Form::Plain => 1 + uleb(s.len()) + 8 * s.len() as u64,
+            Form::Uuid { .. } => 3 + 1 + 128,
+            Form::Hex { .. } => 3 + 1 + uleb(s.len()) + 4 * s.len() as u64,
+            Form::Base64(bytes) => 3 + uleb(bytes.len()) + 8 * bytes.len() as u64,
+            Form::Decimal(n) => 3 + 8 * varint::uleb128_len(*n) as u64,
+        }
+    }
+}
+
+/// 字母部分统一大小写时返回是否为大写；大小写混用返回 None
+fn uniform_case(s: &str) -> Option<bool> {
+    let lower = s.bytes().any(|b| b.is_ascii_lowercase());
+    let upper = s.bytes().any(|b| b.is_ascii_uppercase());
+    (!(lower && upper)).then_some(upper)
+}
+
+fn nibble(b: u8) -> u8 {
+    match b {
+        b'0'..=b'9' => b - b'0',
+        _ => (b | 0x20) - b'a' + 10,
+    }
+}
+
+fn hex_digit(n: u8, upper: bool) -> u8 {
+    match n {
+        0..=9 => b'0' + n,
+        _ if upper => b'A' + n - 10,
+        _ => b'a' + n - 10,
+    }
+}
+
+fn candidates(s: &str) -> Vec<Form> {
+    let mut forms = vec![Form::Plain];
+    let b = s.as_bytes();
+    let case = uniform_case(s);
+    if b.len() == 36 && [8, 13, 18, 23].iter().all(|&i| b[i] == b'-') {
+        let digits: Vec<u8> = b.iter().copied().filter(|&c| c != b'-').collect();
+        if let (32, Some(upper)) = (digits.iter().filter(|c| c.is_ascii_hexdigit()).count(), case) {
+            let mut bytes = [0u8; 16];
+            for (i, pair) in digits.chunks(2).enumerate() { bytes[i] = nibble(pair[0]) << 4 | nibble(pair[1]); }
+            forms.push(Form::Uuid { upper, bytes });
+        }
+    }
+    if let (false, Some(upper)) = (b.is_empty(), case)
+        && b.iter().all(u8::is_ascii_hexdigit)
+    {
+        forms.push(Form::Hex { upper });
+    }
+    if let Ok(bytes) = STANDARD.decode(s)
+        && STANDARD.encode(&bytes) == s
+    {
+        forms.push(Form::Base64(bytes));
+    }
+    if (b == b"0" || (b.first().is_some_and(|&c| c != b'0') && b.iter().all(u8::is_ascii_digit)))
+        && let Ok(n) = s.parse::<u64>()
+    {
+        forms.push(Form::Decimal(n));
+    }
+    forms
+}
+
+/// 写入子类型标记与载荷；选位数最少的写法，相同时取靠前者
+fn write_subtype(writer: &mut BitWriter, s: &str) {
+    let form = candidates(s).into_iter().min_by_key(|f| f.bits(s)).expect("plain form is always a candidate");
+    let mark = |writer: &mut BitWriter, k: u64| { writer.write_bits(1, 1); writer.write_bits(k, 2); };
+    match form {
+        Form::Plain => {
+            writer.write_bits(0, 1);
+            write_plain(writer, s);
+        }
+        Form::Uuid { upper, bytes } => {
+            mark(writer, kind::UUID);
+            writer.write_bits(upper as u64, 1);
+            for b in bytes { writer.write_byte(b); }
+        }
+        Form::Hex { upper } => {
+            mark(writer, kind::HEX);
+            writer.write_bits(upper as u64, 1);
+            varint::write_uleb128(writer, s.len() as u64);
+            for &b in s.as_bytes() { writer.write_bits(nibble(b) as u64, 4); }
+        }
+        Form::Base64(bytes) => {
+            mark(writer, kind::BASE64);
+            varint::write_uleb128(writer, bytes.len() as u64);
+            for b in bytes { writer.write_byte(b); }
+        }
+        Form::Decimal(n) => {
+            mark(writer, kind::DECIMAL);
+            varint::write_uleb128(writer, n);
+        }
+    }
+}
+
+fn read_subtype(reader: &mut BitReader, buf: &mut Vec<u8>) -> Result<Option<&'static str>, Error> {
+    if reader.read_bits(1)? == 0 {
+        read_plain(reader, buf)?;
+        return Ok(None);
+    }
+    let name = match reader.read_bits(2)? {
+        kind::UUID => {
+            let upper = reader.read_bits(1)? == 1;
+            for i in 0..16 {
+                if matches!(i, 4 | 6 | 8 | 10) { buf.push(b'-'); }
+                let b = reader.read_byte()?;
+                buf.extend([hex_digit(b >> 4, upper), hex_digit(b & 0xf, upper)]);
+            }
+            "uuid"
+        }
+        kind::HEX => {
+            let upper = reader.read_bits(1)? == 1;
+            let len = varint::read_uleb128(reader)? as usize;
+            buf.reserve(len.min(reader.remaining_bits() / 4));
+            for _ in 0..len { buf.push(hex_digit(reader.read_bits(4)? as u8, upper)); }
+            "hex"
+        }
+        kind::BASE64 => {
+            let len = varint::read_uleb128(reader)? as usize;
+            let mut bytes = Vec::with_capacity(len.min(reader.remaining_bits() / 8));
+            for _ in 0..len { bytes.push(reader.read_byte()?); }
+            buf.extend(STANDARD.encode(bytes).into_bytes());
+            "base64"
+        }
+        _ => {
+            buf.extend(varint::read_uleb128(reader)?.to_string().into_bytes());
+            "decimal"
+        }
+    };
+    Ok(Some(name))
+}
+
+fn skip_subtype(reader: &mut BitReader) -> Result<(), Error> {
+    let (len, unit) = if reader.read_bits(1)? == 0 {
+        (varint::read_uleb128(reader)?, 8)
+    } else {
+        match reader.read_bits(2)? {
+            kind::UUID => (1 + 128, 1),
+            kind::HEX => {
+                reader.read_bits(1)?;
+                (varint::read_uleb128(reader)?, 4)
+            }
+            kind::BASE64 => (varint::read_uleb128(reader)?, 8),
+            _ => { varint::read_uleb128(reader)?; (0, 0) }
+        }
+    };
+    skip_units(reader, len, unit)
+}
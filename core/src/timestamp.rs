@@ -0,0 +1,145 @@
+use std::cell::Cell;
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    varint, Error,
+};
+
+/// RFC 3339 时间戳 `YYYY-MM-DDTHH:MM:SS[.fffffffff](Z|±HH:MM)` 的各字段；
+/// 仅接受可由字段原样重建的写法（大写 `T`/`Z`、字段定宽且在合法范围内、不含闰秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Timestamp {
+    /// 按本地字段（不含偏移）计算的 Unix 秒数
+    secs: i64,
+    /// 小数位数（0–9），保留末尾的 0
+    digits: u8,
+    frac: u32,
+    offset: Offset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Offset {
+    Utc,
+    /// 分钟数与符号；`-00:00` 与 `+00:00` 区分保存
+    Minutes { west: bool, minutes: u16 },
+}
+
+const SECS_PER_DAY: i64 = 86_400;
+/// 0000-01-01 与 9999-12-31 相对 1970-01-01 的天数
+const MIN_DAYS: i64 = -719_528;
+const MAX_DAYS: i64 = 2_932_896;
+
+/// 公历日期 -> 相对 1970-01-01 的天数
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// 相对 1970-01-01 的天数 -> (年, 月, 日)
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(m <= 2), m, d)
+}
+
+fn days_in_month(y: i64, m: i64) -> i64 {
+    match m {
+        2 if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// 定宽十进制字段
+fn digits(b: &[u8]) -> Option<i64> {
+    b.iter().try_fold(0i64, |n, &c| c.is_ascii_digit().then(|| n * 10 + (c - b'0') as i64))
+}
+
+impl Timestamp {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let b = s.as_bytes();
+        if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || b[10] != b'T' || b[13] != b':' || b[16] != b':' { return None; }
+        let (y, mo, d) = (digits(&b[0..4])?, digits(&b[5..7])?, digits(&b[8..10])?);
+        let (h, mi, sec) = (digits(&b[11..13])?, digits(&b[14..16])?, digits(&b[17..19])?);
+        if !(1..=12).contains(&mo) || d < 1 || d > days_in_month(y, mo) || h > 23 || mi > 59 || sec > 59 { return None; }
+        let mut rest = &b[19..];
+        let (mut n, mut frac) = (0u8, 0u32);
+        if let Some(tail) = rest.strip_prefix(b".") {
+            let len = tail.iter().take_while(|c| c.is_ascii_digit()).count();
+            if !(1..=9).contains(&len) { return None; }
+            (n, frac) = (len as u8, digits(&tail[..len])? as u32);
+            rest = &tail[len..];
+        }
+        let offset = match rest {
+            b"Z" => Offset::Utc,
+            [sign @ (b'+' | b'-'), hh @ .., b':', _, _] if hh.len() == 2 => {
+                let (oh, om) = (digits(hh)?, digits(&rest[4..6])?);
+                if oh > 23 || om > 59 { return None; }
+                Offset::Minutes { west: *sign == b'-', minutes: (oh * 60 + om) as u16 }
+            }
+            _ => return None,
+        };
+        let secs = days_from_civil(y, mo, d) * SECS_PER_DAY + h * 3600 + mi * 60 + sec;
+        Some(Self { secs, digits: n, frac, offset })
+    }
+
+    /// 还原原字符串
+    pub(crate) fn format(&self, buf: &mut Vec<u8>) {
+        use std::io::Write;
+        let (y, mo, d) = civil_from_days(self.secs.div_euclid(SECS_PER_DAY));
+        let t = self.secs.rem_euclid(SECS_PER_DAY);
+        // 写入 Vec 不会失败
+        let _ = write!(buf, "{y:04}-{mo:02}-{d:02}T{:02}:{:02}:{:02}", t / 3600, t / 60 % 60, t % 60);
+        if self.digits > 0 { let _ = write!(buf, ".{:0width$}", self.frac, width = self.digits as usize); }
+        match self.offset {
+            Offset::Utc => buf.push(b'Z'),
+            Offset::Minutes { west, minutes } => {
+                let _ = write!(buf, "{}{:02}:{:02}", if west { '-' } else { '+' }, minutes / 60, minutes % 60);
+            }
+        }
+    }
+
+    /// 秒数写为与上一个时间戳之差（SLEB128），其后 4 位小数位数 + [小数 ULEB128] + 偏移
+    pub(crate) fn write(&self, writer: &mut BitWriter, last: &Cell<i64>) {
+        varint::write_sleb128(writer, self.secs - last.replace(self.secs));
+        writer.write_bits(self.digits as u64, 4);
+        if self.digits > 0 { varint::write_uleb128(writer, self.frac as u64); }
+        match self.offset {
+            Offset::Utc => writer.write_bits(0, 1),
+            Offset::Minutes { west, minutes } => {
+                writer.write_bits(1, 1);
+                writer.write_bits(west as u64, 1);
+                varint::write_uleb128(writer, minutes as u64);
+            }
+        }
+    }
+
+    pub(crate) fn read(reader: &mut BitReader, last: &Cell<i64>) -> Result<Self, Error> {
+        let secs = last.get().checked_add(varint::read_sleb128(reader)?).ok_or(Error::InvalidTimestamp)?;
+        if !(MIN_DAYS * SECS_PER_DAY..(MAX_DAYS + 1) * SECS_PER_DAY).contains(&secs) { return Err(Error::InvalidTimestamp); }
+        last.set(secs);
+        let digits = reader.read_bits(4)? as u8;
+        let frac = if digits > 0 { varint::read_uleb128(reader)? } else { 0 };
+        if digits > 9 || frac >= 10u64.pow(digits as u32) { return Err(Error::InvalidTimestamp); }
+        let offset = if reader.read_bits(1)? == 0 {
+            Offset::Utc
+        } else {
+            let west = reader.read_bits(1)? == 1;
+            let minutes = varint::read_uleb128(reader)?;
+            if minutes >= 24 * 60 { return Err(Error::InvalidTimestamp); }
+            Offset::Minutes { west, minutes: minutes as u16 }
+        };
+        Ok(Self { secs, digits, frac: frac as u32, offset })
+    }
+}
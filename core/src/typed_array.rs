@@ -0,0 +1,133 @@
+use serde_json::Value;
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    non_finite::NonFinite,
+    Error,
+};
+
+/// TYPED_ARRAYS 下数据区数组在 is_pool_ref 位后的类型标记：`0` 为逐元素编码，`1` 后接 2 位元素类型
+mod kind {
+    /// 每元素 64 位 IEEE 754
+    pub const F64: u64 = 0;
+    /// 每元素 32 位 IEEE 754；仅当全部元素转为 f32 后无损
+    pub const F32: u64 = 1;
+    /// 每元素 1 位
+    pub const BOOL: u64 = 2;
+}
+
+/// 定型数组的元素
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Elem {
+    Float(f64),
+    Bool(bool),
+}
+
+impl Elem {
+    /// 可定型的元素：浮点数（非整数）与布尔值
+    pub(crate) fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::Bool(b) => Some(Elem::Bool(*b)),
+            Value::Number(n) if n.is_f64() => n.as_f64().map(Elem::Float),
+            _ => None,
+        }
+    }
+}
+
+impl Elem {
+    /// 元素对应的 `Value`；`non_finite` 决定 NaN/±Inf 的表示
+    pub(crate) fn value(self, non_finite: NonFinite) -> Value {
+        match self {
+            Elem::Float(f) => non_finite.value(f),
+            Elem::Bool(b) => Value::Bool(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    F64,
+    F32,
+    Bool,
+}
+
+impl Kind {
+    /// 元素全为浮点或全为布尔时的写法；空数组与混合类型返回 None
+    pub(crate) fn of(elems: impl IntoIterator<Item = Option<Elem>>) -> Option<Self> {
+        let (mut floats, mut bools, mut exact) = (0usize, 0usize, true);
+        for elem in elems {
+            match elem? {
+                Elem::Float(f) => {
+                    floats += 1;
+                    exact &= f as f32 as f64 == f;
+                }
+                Elem::Bool(_) => bools += 1,
+            }
+        }
+        match (floats, bools) {
+            (0, 0) => None,
+            (0, _) => Some(Kind::Bool),
+            (_, 0) => Some(if exact { Kind::F32 } else { Kind::F64 }),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Kind::F64 => "f64",
+            Kind::F32 => "f32",
+            Kind::Bool => "bool",
+        }
+    }
+
+    /// 每元素位数
+    pub(crate) fn bits(self) -> u64 {
+        match self {
+            Kind::F64 => 64,
+            Kind::F32 => 32,
+            Kind::Bool => 1,
+        }
+    }
+
+    /// 写入一个元素；元素须与 `Kind::of` 判定时一致
+    pub(crate) fn write(self, writer: &mut BitWriter, elem: Elem) {
+        match (self, elem) {
+            (Kind::F64, Elem::Float(f)) => writer.write_bits(f.to_bits(), 64),
+            (Kind::F32, Elem::Float(f)) => writer.write_bits((f as f32).to_bits() as u64, 32),
+            (Kind::Bool, Elem::Bool(b)) => writer.write_bits(b as u64, 1),
+            _ => unreachable!("element does not match typed array kind"),
+        }
+    }
+
+    pub(crate) fn read(self, reader: &mut BitReader) -> Result<Elem, Error> {
+        // 浮点数是否允许非有限值由调用方按包头校验
+        Ok(match self {
+            Kind::Bool => Elem::Bool(reader.read_bits(1)? == 1),
+            Kind::F64 => Elem::Float(f64::from_bits(reader.read_bits(64)?)),
+            Kind::F32 => Elem::Float(f32::from_bits(reader.read_bits(32)? as u32) as f64),
+        })
+    }
+
+    /// 跳过 `count` 个元素
+    pub(crate) fn skip(self, reader: &mut BitReader, count: u64) -> Result<(), Error> {
+        let bits = count.checked_mul(self.bits()).and_then(|n| usize::try_from(n).ok()).ok_or(Error::BitstreamOutOfBounds)?;
+        reader.skip_bits(bits)
+    }
+}
+
+/// 写入类型标记：可定型时为 `1` + 2 位类型，否则为 `0`
+pub(crate) fn write_marker(writer: &mut BitWriter, kind: Option<Kind>) {
+    let Some(kind) = kind else { return writer.write_bits(0, 1) };
+    writer.write_bits(1, 1);
+    writer.write_bits(match kind { Kind::F64 => kind::F64, Kind::F32 => kind::F32, Kind::Bool => kind::BOOL }, 2);
+}
+
+pub(crate) fn read_marker(reader: &mut BitReader) -> Result<Option<Kind>, Error> {
+    if reader.read_bits(1)? == 0 { return Ok(None); }
+    match reader.read_bits(2)? {
+        kind::F64 => Ok(Some(Kind::F64)),
+        kind::F32 => Ok(Some(Kind::F32)),
+        kind::BOOL => Ok(Some(Kind::Bool)),
+        k => Err(Error::InvalidTypedArray(k as u8)),
+    }
+}
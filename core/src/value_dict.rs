@@ -0,0 +1,88 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    bitstream::{BitReader, BitWriter},
+    dict,
+    huffman::HuffmanCodec,
+    varint,
+    Error,
+};
+
+/// 逐键值字典：取值种类少的键（如 status/country/level），其直接字符串值改用该键专属的
+/// canonical Huffman 码写入。表内包含该键下出现过的全部字符串值，因此无需转义位。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ValueDicts {
+    // 按键名排序写入，保证确定性
+    tables: BTreeMap<String, ValueDict>,
+}
+
+#[derive(Debug, Clone)]
+struct ValueDict {
+    freq: HashMap<String, u64>,
+    codec: HuffmanCodec,
+}
+
+impl ValueDicts {
+    /// 按位代价挑选值得建表的键：建表开销加逐次码字须少于逐次内联写入
+    /// `stats` 为各键下直接字符串值的频次；`pooled` 时内联字符串额外带 1 位 is_pool_ref
+    pub fn select(stats: &HashMap<&str, HashMap<&str, u32>>, key_codec: &HuffmanCodec, pooled: bool) -> Result<Self, Error> {
+        let mut tables = BTreeMap::new();
+        for (&key, values) in stats {
+            let Some((_, key_code_len)) = key_codec.try_get_code(key) else { continue };
+            let freq: HashMap<String, u64> = values.iter().map(|(&s, &f)| (s.to_string(), f as u64)).collect();
+            let codec = HuffmanCodec::from_frequencies(&freq)?;
+
+            let mut inline_bits = 0u64;
+            let mut table_bits = key_code_len as u64 + 8 * varint::uleb128_len(freq.len() as u64) as u64;
+            for (s, &f) in &freq {
+                let body = 8 * (varint::uleb128_len(s.len() as u64) + s.len()) as u64;
+                inline_bits += f * (body + pooled as u64);
+                let (_, code_len) = codec.try_get_code(s).ok_or(Error::HuffmanError)?;
+                table_bits += body + 8 * varint::uleb128_len(f) as u64 + f * code_len as u64;
+            }
+            if table_bits < inline_bits {
+                tables.insert(key.to_string(), ValueDict { freq, codec });
+            }
+        }
+        Ok(Self { tables })
+    }
+
+    pub fn is_empty(&self) -> bool { self.tables.is_empty() }
+
+    /// 该键的值码表
+    pub fn get(&self, key: &str) -> Option<&HuffmanCodec> { self.tables.get(key).map(|t| &t.codec) }
+
+    /// 该键的值频次表
+    pub fn frequencies(&self, key: &str) -> Option<&HashMap<String, u64>> { self.tables.get(key).map(|t| &t.freq) }
+
+    /// 已建表的键名
+    pub fn keys(&self) -> impl Iterator<Item = &str> { self.tables.keys().map(String::as_str) }
+
+    /// 格式：COUNT(uleb128) + 对每个键：[KEY 的 Huffman 码][值字典，格式同字典区]
+    pub fn write(&self, writer: &mut BitWriter, key_codec: &HuffmanCodec) -> Result<(), Error> {
+        varint::write_uleb128(writer, self.tables.len() as u64);
+        for (key, table) in &self.tables {
+            key_codec.write_key_code(key, writer)?;
+            dict::write_dictionary(writer, &table.freq);
+        }
+        Ok(())
+    }
+
+    pub fn read(reader: &mut BitReader, key_codec: &HuffmanCodec) -> Result<Self, Error> {
+        let count = varint::read_uleb128(reader)?;
+        let mut dicts = Self::default();
+        for _ in 0..count {
+            let key = key_codec.decode_key(reader)?;
+            dicts.insert(key, dict::read_dictionary(reader)?)?;
+        }
+        Ok(dicts)
+    }
+
+    /// 由值频次表为某键建表；空表视为损坏数据
+    pub fn insert(&mut self, key: String, freq: HashMap<String, u64>) -> Result<(), Error> {
+        if freq.is_empty() { return Err(Error::HuffmanError); }
+        let codec = HuffmanCodec::from_frequencies(&freq)?;
+        self.tables.insert(key, ValueDict { freq, codec });
+        Ok(())
+    }
+}
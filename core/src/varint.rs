@@ -11,6 +11,13 @@ pub fn write_uleb128(writer: &mut BitWriter, mut value: u64) {
     }
 }
 
+/// ULEB128 编码后的字节数
+pub fn uleb128_len(mut value: u64) -> usize {
+    let mut n = 1;
+    while value >= 0x80 { value >>= 7; n += 1; }
+    n
+}
+
 /// 读取 ULEB128 编码的无符号整数
 pub fn read_uleb128(reader: &mut BitReader) -> Result<u64, Error> {
     let mut value: u64 = 0;
@@ -0,0 +1,146 @@
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::{decode::{self, DecodeOptions}, non_finite::NonFinite, Error};
+
+/// 事件式（SAX 风格）解码回调，按文档顺序触发；默认实现均不做任何事。
+/// 对象成员依次触发 `key` 与其值的事件；整数在 i64 范围内走 `value_int`，否则走 `value_uint`
+#[allow(unused_variables)]
+pub trait Visitor {
+    /// `len` 为成员数，来自压缩数据本身
+    fn start_object(&mut self, len: usize) {}
+    fn key(&mut self, key: &str) {}
+    fn end_object(&mut self) {}
+    /// `len` 为元素数，来自压缩数据本身
+    fn start_array(&mut self, len: usize) {}
+    fn end_array(&mut self) {}
+    fn value_null(&mut self) {}
+    fn value_bool(&mut self, value: bool) {}
+    fn value_int(&mut self, value: i64) {}
+    fn value_uint(&mut self, value: u64) {}
+    /// 仅 NON_FINITE 压缩包可能给出 NaN/±Inf
+    fn value_float(&mut self, value: f64) {}
+    fn value_str(&mut self, value: &str) {}
+    /// 值池引用处的完整条目；默认按事件重放，可改写以直接使用已解码的值
+    fn pooled(&mut self, value: &Value) { replay(value, self) }
+    /// 由注册的编解码器还原的扩展值；默认按事件重放
+    fn extension(&mut self, value: &Value) { replay(value, self) }
+    /// 下一个条目（值、对象键或定型数组元素）在位流中的起始位偏移；供反汇编使用，非稳定接口
+    #[doc(hidden)]
+    fn bit_offset(&mut self, offset: u64) {}
+    /// 随后一个值的编码细节（值字典、值池引用、字符串写法、定型元素类型、扩展类型）；供反汇编使用，非稳定接口
+    #[doc(hidden)]
+    fn encoding(&mut self, detail: fmt::Arguments<'_>) {}
+}
+
+/// 将已解码的值按事件顺序重放给 visitor
+fn replay<V: Visitor + ?Sized>(value: &Value, visitor: &mut V) {
+    match value {
+        Value::Null => visitor.value_null(),
+        Value::Bool(b) => visitor.value_bool(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => visitor.value_int(i),
+            (None, Some(u)) => visitor.value_uint(u),
+            _ => visitor.value_float(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => visitor.value_str(s),
+        Value::Array(arr) => {
+            visitor.start_array(arr.len());
+            for item in arr { replay(item, visitor); }
+            visitor.end_array();
+        }
+        Value::Object(map) => {
+            visitor.start_object(map.len());
+            for (k, v) in map {
+                visitor.key(k);
+                replay(v, visitor);
+            }
+            visitor.end_object();
+        }
+    }
+}
+
+/// 事件式解码：不构建 `serde_json::Value`，按文档顺序回调 visitor。
+/// 出错时已触发的事件不会撤回
+pub fn decode_with_visitor<V: Visitor + ?Sized>(bytes: &[u8], visitor: &mut V) -> Result<(), Error> {
+    decode::visit_with(bytes, &DecodeOptions::default(), None, visitor)
+}
+
+// 长度来自数据本身，预分配设上限，避免损坏数据导致超大分配
+const PREALLOC_LIMIT: usize = 1 << 12;
+
+enum Frame {
+    Array(Vec<Value>),
+    Object(Map<String, Value>, Option<String>),
+}
+
+/// 由事件重建 `serde_json::Value`，即默认解码器
+#[derive(Default)]
+pub(crate) struct ValueBuilder {
+    stack: Vec<Frame>,
+    root: Option<Value>,
+    non_finite: NonFinite,
+}
+
+impl ValueBuilder {
+    /// `non_finite` 决定 NaN/±Inf 的表示
+    pub fn new(non_finite: NonFinite) -> Self {
+        Self { non_finite, ..Default::default() }
+    }
+
+    fn push(&mut self, value: Value) {
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some(Frame::Array(arr)) => arr.push(value),
+            Some(Frame::Object(map, key)) => {
+                map.insert(key.take().unwrap_or_default(), value);
+            }
+        }
+    }
+
+    /// 取出根值；解码成功后必然存在
+    pub fn finish(self) -> Value { self.root.unwrap_or(Value::Null) }
+}
+
+impl Visitor for ValueBuilder {
+    fn start_object(&mut self, len: usize) {
+        self.stack.push(Frame::Object(Map::with_capacity(len.min(PREALLOC_LIMIT)), None));
+    }
+
+    fn key(&mut self, key: &str) {
+        if let Some(Frame::Object(_, slot)) = self.stack.last_mut() { *slot = Some(key.to_string()); }
+    }
+
+    fn end_object(&mut self) {
+        if let Some(Frame::Object(map, _)) = self.stack.pop() { self.push(Value::Object(map)); }
+    }
+
+    fn start_array(&mut self, len: usize) {
+        self.stack.push(Frame::Array(Vec::with_capacity(len.min(PREALLOC_LIMIT))));
+    }
+
+    fn end_array(&mut self) {
+        if let Some(Frame::Array(arr)) = self.stack.pop() { self.push(Value::Array(arr)); }
+    }
+
+    fn value_null(&mut self) { self.push(Value::Null); }
+
+    fn value_bool(&mut self, value: bool) { self.push(Value::Bool(value)); }
+
+    fn value_int(&mut self, value: i64) { self.push(Value::Number(value.into())); }
+
+    fn value_uint(&mut self, value: u64) { self.push(Value::Number(value.into())); }
+
+    fn value_float(&mut self, value: f64) {
+        // 未置位 NON_FINITE 的压缩包中 NaN/Inf 已被解码器拒绝
+        let value = self.non_finite.value(value);
+        self.push(value);
+    }
+
+    fn value_str(&mut self, value: &str) { self.push(Value::String(value.to_string())); }
+
+    fn pooled(&mut self, value: &Value) { self.push(value.clone()); }
+
+    fn extension(&mut self, value: &Value) { self.push(value.clone()); }
+}
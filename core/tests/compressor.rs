@@ -0,0 +1,98 @@
+use json_packer::{
+    compress_to_bytes, decompress_from_bytes, CompressOptions, Compressor, DecodeOptions, Decompressor, Error, PoolConfig,
+    SharedDictionary,
+};
+use serde_json::json;
+
+#[test]
+fn compressor_matches_stateless_output_and_reuses_buffer() {
+    let opt = CompressOptions::default().with_value_pool(true);
+    let mut c = Compressor::new(opt.clone());
+    let docs = [
+        json!({"status": "connected", "tags": ["connected", "connected", "connected"]}),
+        json!({"id": 1, "name": "second"}),
+    ];
+    let mut out = Vec::new();
+    for d in &docs {
+        out.clear();
+        c.compress_into(d, &mut out).unwrap();
+        assert_eq!(out, compress_to_bytes(d, &opt).unwrap());
+    }
+}
+
+#[test]
+fn key_codes_are_reused_only_for_identical_key_frequencies() {
+    let opt = CompressOptions::default().with_value_dicts(true);
+    let mut c = Compressor::new(opt.clone());
+    // 同一结构复用键码表；键集相同而频次不同、或键集变化时重建
+    let docs = [
+        json!({"id": 1, "status": "ok"}),
+        json!({"id": 2, "status": "failed"}),
+        json!([{"id": 3, "status": "ok"}, {"id": 4}]),
+        json!([{"status": "ok"}, {"id": 5, "status": "ok"}]),
+        json!({"id": 6, "status": "ok"}),
+        json!({"other": true}),
+        // 三个键时码长随频次分配到不同的键
+        json!([{"a": 1}, {"a": 2}, {"a": 3, "b": 4, "c": 5}]),
+        json!([{"c": 1}, {"c": 2}, {"a": 3, "b": 4, "c": 5}]),
+    ];
+    for d in &docs {
+        let bytes = c.compress(d).unwrap();
+        assert_eq!(bytes, compress_to_bytes(d, &opt).unwrap(), "{d}");
+        assert_eq!(&decompress_from_bytes(&bytes).unwrap(), d);
+    }
+}
+
+#[test]
+fn compress_into_appends_for_framing() {
+    let mut c = Compressor::new(CompressOptions::default());
+    let d = Decompressor::new(DecodeOptions::default());
+    let docs = [json!({"a": 1}), json!([true, null])];
+    let mut buf = vec![0xEE];
+    for doc in &docs { c.compress_into(doc, &mut buf).unwrap(); }
+    assert_eq!(buf[0], 0xEE);
+
+    let (first, used) = d.decompress_prefix(&buf[1..]).unwrap();
+    let (second, _) = d.decompress_prefix(&buf[1 + used..]).unwrap();
+    assert_eq!([first, second], docs);
+}
+
+#[test]
+fn shared_dictionary_roundtrip_is_smaller() {
+    let samples = [
+        json!({"user_id": 1, "status": "connected", "region": "eu-west"}),
+        json!({"user_id": 2, "status": "connected", "region": "us-east"}),
+        json!({"user_id": 3, "status": "connected", "region": "eu-west"}),
+    ];
+    let dict = SharedDictionary::from_samples(&samples, Some(PoolConfig { min_repeats: 2, min_string_len: 4 })).unwrap();
+    assert!(dict.pool().contains(&"connected".to_string()));
+
+    let opt = CompressOptions::default().with_value_pool(true);
+    let mut c = Compressor::with_dictionary(opt.clone(), dict.clone());
+    let d = Decompressor::with_dictionary(DecodeOptions::default().with_strict(true), dict);
+
+    let doc = json!({"user_id": 4, "status": "connected", "region": "ap-south"});
+    let packed = c.compress(&doc).unwrap();
+    assert_eq!(d.decompress(&packed).unwrap(), doc);
+    assert!(packed.len() < compress_to_bytes(&doc, &opt).unwrap().len());
+
+    // 无字典的解码器无法解码，且报告需要字典
+    let err = decompress_from_bytes(&packed).unwrap_err();
+    assert!(matches!(err.kind(), Error::SharedDictionaryRequired(_)));
+}
+
+#[test]
+fn shared_dictionary_mismatch_and_fallback() {
+    let a = SharedDictionary::from_samples(&[json!({"x": 1})], None).unwrap();
+    let b = SharedDictionary::from_samples(&[json!({"y": 1})], None).unwrap();
+    let mut c = Compressor::with_dictionary(CompressOptions::default(), a);
+
+    let packed = c.compress(&json!({"x": 5})).unwrap();
+    let err = Decompressor::with_dictionary(DecodeOptions::default(), b).decompress(&packed).unwrap_err();
+    assert_eq!(err.code(), "shared_dictionary_mismatch");
+
+    // 字典未覆盖的键：退回自包含格式，任何解码器都能解
+    let doc = json!({"x": 1, "unknown": 2});
+    let packed = c.compress(&doc).unwrap();
+    assert_eq!(decompress_from_bytes(&packed).unwrap(), doc);
+}
@@ -0,0 +1,54 @@
+use json_packer::{compress_to_bytes, disassemble, CompressOptions};
+use serde_json::json;
+
+#[test]
+fn disassemble_full_payload() {
+    let v = json!({"name": "Alice", "tags": ["connected", "connected", "connected"], "n": -3});
+    let opt = CompressOptions::default().with_value_pool(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    let dump = disassemble(&bytes);
+    assert!(dump.error.is_none());
+
+    let texts: Vec<&str> = dump.lines.iter().map(|l| l.text.as_str()).collect();
+    assert!(texts.contains(&"header.version = 2"));
+    assert!(texts.contains(&"key \"name\""));
+    assert!(texts.contains(&"string \"Alice\""));
+    assert!(texts.contains(&"string ref #0 -> \"connected\""));
+    assert!(texts.contains(&"int -3"));
+    // 行按位偏移递增
+    assert!(dump.lines.windows(2).all(|w| w[0].bit_offset <= w[1].bit_offset));
+}
+
+#[test]
+fn disassemble_stops_at_first_error() {
+    let v = json!({"a": "hello world", "b": 1});
+    let mut bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    bytes.truncate(bytes.len() - 4);
+    let dump = disassemble(&bytes);
+    let err = dump.error.as_ref().expect("truncated payload must fail");
+    // 与解码器一致：读越界报告为数据截断
+    assert!(matches!(err.error, json_packer::Error::TruncatedData));
+    let last = dump.lines.last().unwrap();
+    assert!(err.bit_offset >= last.bit_offset);
+    assert!(dump.to_string().contains("!! error"));
+}
+
+#[test]
+fn disassembler_reports_decoder_error_variants() {
+    use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+    // 值池一个字符串条目，引用码长 300 无法作为码长
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::VALUE_POOL | flags::POOL_REF_CODES, ..PackageHeader::new(0, 1) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::STRING as u64, 3);
+    write_uleb128(&mut w, 1);
+    w.write_byte(b'a');
+    write_uleb128(&mut w, 300);
+    let bytes = w.into_bytes();
+
+    let decoded = json_packer::decompress_from_bytes(&bytes).unwrap_err();
+    let dumped = disassemble(&bytes).error.expect("bad code length must fail").error;
+    assert!(matches!(decoded.kind(), json_packer::Error::HuffmanError));
+    assert_eq!(dumped.kind().code(), decoded.code());
+}
@@ -10,7 +10,8 @@ fn bad_magic() {
     bytes.push(0x00); // dict_len
     bytes.push(0x00); // pool_len
     let err = json_packer::decompress_from_bytes(&bytes).unwrap_err();
-    assert!(matches!(err, json_packer::Error::BadMagic));
+    assert!(matches!(err.kind(), json_packer::Error::BadMagic));
+    assert_eq!(err.code(), "bad_magic");
 }
 
 #[test]
@@ -22,7 +23,7 @@ fn bad_version() {
     bytes.push(0x00); // dict_len
     bytes.push(0x00); // pool_len
     let err = json_packer::decompress_from_bytes(&bytes).unwrap_err();
-    assert!(matches!(err, json_packer::Error::BadVersion));
+    assert!(matches!(err.kind(), json_packer::Error::BadVersion));
 }
 
 #[test]
@@ -31,8 +32,10 @@ fn truncated_data() {
     let mut bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
     bytes.truncate(bytes.len().saturating_sub(3));
     let err = json_packer::decompress_from_bytes(&bytes).unwrap_err();
-    // 可能触发 BitstreamOutOfBounds 或 VarintError
-    matches!(err, json_packer::Error::BitstreamOutOfBounds | json_packer::Error::VarintError);
+    // 读越界在解码时统一报告为截断
+    assert!(matches!(err.kind(), json_packer::Error::TruncatedData));
+    assert_eq!(err.code(), "truncated_data");
+    assert!(err.bit_offset().unwrap() <= bytes.len() as u64 * 8);
 }
 
 #[test]
@@ -53,5 +56,18 @@ fn utf8_error_in_string() {
     bytes.push(0xFF);
 
     let err = json_packer::decompress_from_bytes(&bytes).unwrap_err();
-    matches!(err, json_packer::Error::Utf8(_));
+    assert!(matches!(err.kind(), json_packer::Error::Utf8(_)));
+    assert_eq!(err.path(), Some(""));
+}
+
+#[test]
+fn error_carries_json_path_and_offset() {
+    let v = json!({"items": [1, {"na/me": "hello world"}]});
+    let mut bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    bytes.truncate(bytes.len() - 3);
+    let err = json_packer::decompress_from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err.kind(), json_packer::Error::TruncatedData));
+    assert_eq!(err.path(), Some("/items/1/na~1me"));
+    let offset = err.bit_offset().unwrap();
+    assert!(err.to_string().contains(&format!("at bit {offset}")));
 }
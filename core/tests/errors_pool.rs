@@ -15,7 +15,7 @@ fn pool_missing_should_error() {
     w.write_bits(0, 8); // uleb128(0) 一个字节 0x00
     let bytes = w.into_bytes();
     let err = decompress_from_bytes(&bytes).unwrap_err();
-    assert!(matches!(err, Error::PoolIdOutOfRange));
+    assert!(matches!(err.kind(), Error::PoolIdOutOfRange));
 }
 
 #[test]
@@ -31,5 +31,5 @@ fn pool_id_out_of_range() {
     w.write_bits(1, 8); // 写一个字节 0x01
     let bytes = w.into_bytes();
     let err = decompress_from_bytes(&bytes).unwrap_err();
-    assert!(matches!(err, Error::PoolIdOutOfRange));
+    assert!(matches!(err.kind(), Error::PoolIdOutOfRange));
 }
@@ -0,0 +1,45 @@
+use json_packer::{compress_to_bytes, estimate_size, inspect, CompressOptions, FloatPrecision};
+use serde_json::json;
+
+fn doc() -> serde_json::Value {
+    let tags = json!(["alpha", "beta", "gamma", "delta"]);
+    let items: Vec<_> = (0..12)
+        .map(|i| json!({"id": i, "level": if i % 3 == 0 { "warn" } else { "info" }, "host": "server-01.example.com", "tags": tags, "ratio": 0.5}))
+        .collect();
+    json!({"items": items})
+}
+
+#[test]
+fn estimate_matches_encoded_size_and_sections() {
+    let v = doc();
+    let all = CompressOptions::default().with_value_pool(true).with_subtree_pool(true).with_huffman_pool_refs(true).with_value_dicts(true);
+    for opt in [CompressOptions::default(), CompressOptions::default().with_value_pool(true), all] {
+        let est = estimate_size(&v, &opt).unwrap();
+        let bytes = compress_to_bytes(&v, &opt).unwrap();
+        assert_eq!(est.total_bytes(), bytes.len());
+
+        let info = inspect(&bytes).unwrap();
+        assert_eq!(est.header_bits, info.header.bit_len);
+        assert_eq!(est.dict_bits, info.dict.bit_len);
+        assert_eq!(est.pool_bits, info.pool_section.bit_len);
+        assert_eq!(est.value_dicts_bits, info.value_dicts.bit_len);
+        assert_eq!(est.body_bits, info.body.bit_len);
+    }
+}
+
+#[test]
+fn estimate_reports_json_text_size() {
+    let v = doc();
+    let est = estimate_size(&v, &CompressOptions::default()).unwrap();
+    assert_eq!(est.json_bytes, serde_json::to_string(&v).unwrap().len());
+    assert!(est.ratio() < 1.0);
+}
+
+#[test]
+fn json_size_is_measured_on_the_input_not_the_quantized_copy() {
+    let v = json!({"lat": 48.858370123456, "lon": 2.294481456789});
+    let opt = CompressOptions::default().with_float_precision(Some(FloatPrecision::Decimals(2)));
+    let est = estimate_size(&v, &opt).unwrap();
+    assert_eq!(est.json_bytes, serde_json::to_string(&v).unwrap().len());
+    assert_eq!(est.total_bytes(), compress_to_bytes(&v, &opt).unwrap().len());
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decode_base64, decompress_from_bytes, decompress_with_options, disassemble, encode_base64, inspect, CompressOptions, DecodeOptions, Decompressor, Error, Extension, UnknownExtension};
+use serde_json::{json, Value};
+
+/// `{"$binary": "<base64>"}` -> 原始字节
+#[derive(Debug)]
+struct Binary;
+
+impl Extension for Binary {
+    fn id(&self) -> u64 { 1 }
+
+    fn encode(&self, value: &Value) -> Option<Vec<u8>> {
+        let map = value.as_object().filter(|m| m.len() == 1)?;
+        let bytes = decode_base64(map.get("$binary")?.as_str()?).ok()?;
+        // 非规范 Base64 无法原样还原，按普通 JSON 编码
+        (encode_base64(&bytes) == map["$binary"]).then_some(bytes)
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value, Error> {
+        Ok(json!({"$binary": encode_base64(payload)}))
+    }
+}
+
+/// `{"$date": <毫秒>}` -> 8 字节小端整数
+#[derive(Debug)]
+struct Date;
+
+impl Extension for Date {
+    fn id(&self) -> u64 { 7 }
+
+    fn encode(&self, value: &Value) -> Option<Vec<u8>> {
+        let map = value.as_object().filter(|m| m.len() == 1)?;
+        Some(map.get("$date")?.as_i64()?.to_le_bytes().to_vec())
+    }
+
+    fn decode(&self, payload: &[u8]) -> Result<Value, Error> {
+        let millis = i64::from_le_bytes(payload.try_into().map_err(|_| Error::InvalidExtension(self.id()))?);
+        Ok(json!({"$date": millis}))
+    }
+}
+
+fn codecs() -> Vec<Arc<dyn Extension>> {
+    vec![Arc::new(Binary), Arc::new(Date)]
+}
+
+fn encoded() -> CompressOptions {
+    CompressOptions::default().with_extensions(codecs())
+}
+
+fn decoding(unknown: UnknownExtension) -> DecodeOptions {
+    DecodeOptions::default().with_extensions(codecs()).with_unknown_extensions(unknown)
+}
+
+fn decode(bytes: &[u8]) -> Value {
+    decompress_with_options(bytes, &decoding(UnknownExtension::Error)).unwrap()
+}
+
+#[test]
+fn codec_selection_and_fallback() {
+    let v = json!([
+        {"$binary": "AAEC"},
+        // 空载荷
+        {"$binary": ""},
+        // 非规范 Base64、多余成员、类型不符：均按普通 JSON 编码
+        {"$binary": "AAE="},
+        {"$binary": "AAEC", "x": 1},
+        {"$date": "yesterday"},
+        {"$date": -1},
+        null,
+    ]);
+    let bytes = compress_to_bytes(&v, &encoded()).unwrap();
+    assert_eq!(decode(&bytes), v);
+    let dump = disassemble(&bytes).to_string();
+    assert_eq!(dump.matches("extension type=1 len=3").count(), 1);
+    assert_eq!(dump.matches("extension type=1 len=0").count(), 1);
+    assert_eq!(dump.matches("extension type=7 len=8").count(), 1);
+    assert_eq!(dump.matches("extension type=").count(), 3);
+}
+
+#[test]
+fn first_registered_codec_wins() {
+    #[derive(Debug)]
+    struct AnyDate;
+    impl Extension for AnyDate {
+        fn id(&self) -> u64 { 9 }
+        fn encode(&self, value: &Value) -> Option<Vec<u8>> { value.get("$date").map(|d| d.to_string().into_bytes()) }
+        fn decode(&self, payload: &[u8]) -> Result<Value, Error> {
+            Ok(json!({"$date": serde_json::from_slice::<Value>(payload).map_err(|_| Error::InvalidExtension(9))?}))
+        }
+    }
+    let v = json!({"$date": 5});
+    let first = CompressOptions::default().with_extensions(vec![Arc::new(AnyDate), Arc::new(Date)]);
+    let bytes = compress_to_bytes(&v, &first).unwrap();
+    assert!(disassemble(&bytes).to_string().contains("extension type=9"));
+    let d = DecodeOptions::default().with_extensions(vec![Arc::new(Date), Arc::new(AnyDate)]);
+    assert_eq!(decompress_with_options(&bytes, &d).unwrap(), v);
+}
+
+#[test]
+fn captured_values_are_left_out_of_pools_and_dictionaries() {
+    let blob = json!({"$binary": encode_base64(&[7; 48])});
+    let v = json!({"rows": [blob, blob, blob], "date": {"$date": 0}, "wrapped": [[blob, 1], [blob, 1]]});
+    let opt = encoded().with_value_pool(true).with_pool_min_string_len(0).with_value_dicts(true).with_subtree_pool(true).with_pool_min_subtree_nodes(1);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decode(&bytes), v);
+    // 扩展值内的键与字符串不进入字典与值池，含扩展值的容器不去重
+    let info = inspect(&bytes).unwrap();
+    assert!(info.pool.is_empty(), "{:?}", info.pool);
+    assert!(info.keys.iter().all(|k| !k.key.starts_with('$')));
+    assert_eq!(disassemble(&bytes).to_string().matches("extension type=1").count(), 5);
+
+    // 被接受的字符串不走值字典
+    #[derive(Debug)]
+    struct Shout;
+    impl Extension for Shout {
+        fn id(&self) -> u64 { 3 }
+        fn encode(&self, value: &Value) -> Option<Vec<u8>> { value.as_str().filter(|s| *s == "ON").map(|_| Vec::new()) }
+        fn decode(&self, _: &[u8]) -> Result<Value, Error> { Ok(json!("ON")) }
+    }
+    let v = json!([{"s": "on"}, {"s": "ON"}, {"s": "on"}, {"s": "ON"}, {"s": "off"}, {"s": "on"}]);
+    let opt = CompressOptions::default().with_value_dicts(true).with_extensions(vec![Arc::new(Shout)]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_with_options(&bytes, &DecodeOptions::default().with_extensions(vec![Arc::new(Shout)])).unwrap(), v);
+    let dump = disassemble(&bytes).to_string();
+    assert_eq!(dump.matches("extension type=3").count(), 2);
+    assert_eq!(dump.matches("(value dict)").count(), 4);
+}
+
+#[test]
+fn unknown_extension_under_error_policy() {
+    let v = json!({"a": {"$binary": "AAEC"}, "b": [{"$date": 1}], "z": 1});
+    let bytes = compress_to_bytes(&v, &encoded()).unwrap();
+    let err = decompress_from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err.kind(), Error::UnknownExtension(1)));
+    assert_eq!(err.code(), "unknown_extension");
+    assert_eq!(err.path(), Some("/a"));
+
+    // 仅注册其一：报错位置指向另一个
+    let only_binary = DecodeOptions::default().with_extensions(vec![Arc::new(Binary)]);
+    let err = decompress_with_options(&bytes, &only_binary).unwrap_err();
+    assert!(matches!(err.kind(), Error::UnknownExtension(7)));
+    assert_eq!(err.path(), Some("/b/0"));
+
+    // 投影跳过的扩展值不需要编解码器；命中时照常报错
+    let d = Decompressor::new(DecodeOptions::default());
+    assert_eq!(d.decompress_projected(&bytes, &["/z"]).unwrap(), json!({"z": 1}));
+    assert_eq!(d.decompress_projected(&bytes, &["/b/0"]).unwrap_err().code(), "unknown_extension");
+    assert!(inspect(&bytes).is_ok());
+
+    let skipping = DecodeOptions::default().with_unknown_extensions(UnknownExtension::Null);
+    assert_eq!(decompress_with_options(&bytes, &skipping).unwrap(), json!({"a": null, "b": [null], "z": 1}));
+}
+
+#[test]
+fn codec_rejecting_its_payload_reports_the_path() {
+    #[derive(Debug)]
+    struct Short;
+    impl Extension for Short {
+        fn id(&self) -> u64 { 7 }
+        fn encode(&self, value: &Value) -> Option<Vec<u8>> { value.get("$date").map(|_| vec![0; 3]) }
+        fn decode(&self, payload: &[u8]) -> Result<Value, Error> { Date.decode(payload) }
+    }
+    let bytes = compress_to_bytes(&json!([{"$date": 0}]), &CompressOptions::default().with_extensions(vec![Arc::new(Short)])).unwrap();
+    let err = decompress_with_options(&bytes, &decoding(UnknownExtension::Error)).unwrap_err();
+    assert!(matches!(err.kind(), Error::InvalidExtension(7)));
+    assert_eq!(err.code(), "invalid_extension");
+    assert_eq!(err.path(), Some("/0"));
+}
+
+/// 手工构造：根为单个扩展值
+fn extension_package(id: u64, len: u64, payload: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::EXTENSIONS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::NULL as u64, 3);
+    w.write_bits(1, 1);
+    write_uleb128(&mut w, id);
+    write_uleb128(&mut w, len);
+    for &b in payload { w.write_byte(b); }
+    w.into_bytes()
+}
+
+#[test]
+fn malformed_extension_payloads_are_data_errors() {
+    assert_eq!(decode(&extension_package(1, 2, &[0xff, 0x00])), json!({"$binary": "/wA"}));
+    // 声明长度超出剩余数据：不论是否注册、是否按 null 跳过，都不得按声明长度预分配
+    for (id, len) in [(1, 3), (1, u64::MAX), (42, 1 << 40), (u64::MAX, u64::MAX >> 3)] {
+        let bytes = extension_package(id, len, &[0xff, 0x00]);
+        for unknown in [UnknownExtension::Error, UnknownExtension::Null] {
+            let err = decompress_with_options(&bytes, &decoding(unknown)).unwrap_err();
+            assert_eq!(err.code(), "truncated_data", "{id} x {len}");
+        }
+        // 投影不论是否命中都要跳过整个根值
+        let d = Decompressor::new(decoding(UnknownExtension::Null));
+        assert!(d.decompress_projected(&bytes, &["/x"]).is_err());
+        assert!(disassemble(&bytes).error.is_some());
+    }
+    // 类型 ID 的变长整数超出 u64
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::EXTENSIONS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::NULL as u64, 3);
+    w.write_bits(1, 1);
+    for _ in 0..10 { w.write_byte(0xff); }
+    w.write_byte(0x01);
+    assert_eq!(decompress_from_bytes(&w.into_bytes()).unwrap_err().code(), "varint_error");
+}
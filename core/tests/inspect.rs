@@ -0,0 +1,35 @@
+use json_packer::{compress_to_bytes, inspect, CompressOptions, VERSION_V1, VERSION_V2};
+use serde_json::json;
+
+#[test]
+fn inspect_reports_keys_and_contiguous_sections() {
+    let v = json!([{"name": "a", "id": 1}, {"name": "b", "id": 2}, {"name": "c"}]);
+    let bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    let info = inspect(&bytes).unwrap();
+
+    assert_eq!(info.version, VERSION_V1);
+    assert!(!info.has_pool());
+    let keys: Vec<_> = info.keys.iter().map(|k| (k.key.as_str(), k.frequency)).collect();
+    assert_eq!(keys, vec![("id", 2), ("name", 3)]);
+    assert!(info.keys.iter().all(|k| k.code_len >= 1));
+
+    assert_eq!(info.header.bit_offset, 0);
+    assert_eq!(info.header.bit_len, 7 * 8);
+    assert_eq!(info.dict.bit_offset, info.header.bit_offset + info.header.bit_len);
+    assert_eq!(info.pool_section.bit_offset, info.dict.bit_offset + info.dict.bit_len);
+    assert_eq!(info.pool_section.bit_len, 0);
+    assert_eq!(info.body.bit_offset, info.pool_section.bit_offset + info.pool_section.bit_len);
+    let end = info.body.bit_offset + info.body.bit_len;
+    assert_eq!(end.div_ceil(8) as usize, info.total_bytes);
+}
+
+#[test]
+fn inspect_lists_pool_entries() {
+    let v = json!(["connected", "connected", "connected", "short"]);
+    let opt = CompressOptions::default().with_value_pool(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    let info = inspect(&bytes).unwrap();
+    assert_eq!(info.version, VERSION_V2);
+    assert_eq!(info.pool, vec![json!("connected")]);
+    assert!(info.pool_section.bit_len > 0);
+}
@@ -16,7 +16,7 @@ fn v2_string_pool_roundtrip_and_benefit() {
 
     let bytes_v1 = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
 
-    let opt = CompressOptions { enable_value_pool: true, pool_min_repeats: 3, pool_min_string_len: 8 };
+    let opt = CompressOptions::default().with_value_pool(true).with_pool_min_repeats(3).with_pool_min_string_len(8);
     let bytes_v2 = compress_with_options(&v, &opt).unwrap();
     let out_v2 = decompress_from_bytes(&bytes_v2).unwrap();
     assert_eq!(v, out_v2);
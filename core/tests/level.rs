@@ -0,0 +1,60 @@
+use json_packer::{compress_to_bytes, compress_with_level, decompress_from_bytes, CompressOptions, Level, Metadata};
+use serde_json::json;
+
+fn doc() -> serde_json::Value {
+    let addr = json!({"street": "1 Main St", "city": "Springfield", "zip": "12345"});
+    let items: Vec<_> = (0..20)
+        .map(|i| json!({"id": i, "status": if i % 2 == 0 { "connected" } else { "idle" }, "address": addr}))
+        .collect();
+    json!({"items": items})
+}
+
+#[test]
+fn max_is_never_larger_and_reports_its_choice() {
+    let v = doc();
+    let base = CompressOptions::default();
+    let fast = compress_with_level(&v, Level::Fast, &base).unwrap();
+    let default = compress_with_level(&v, Level::Default, &base).unwrap();
+    let max = compress_with_level(&v, Level::Max, &base).unwrap();
+    assert!(max.bytes.len() <= fast.bytes.len());
+    assert!(max.bytes.len() <= default.bytes.len());
+    assert_eq!(decompress_from_bytes(&max.bytes).unwrap(), v);
+    // 报告的选项可复现同一输出
+    assert_eq!(compress_to_bytes(&v, &max.options).unwrap(), max.bytes);
+    // 确定性
+    assert_eq!(compress_with_level(&v, Level::Max, &base).unwrap().bytes, max.bytes);
+}
+
+#[test]
+fn presets_keep_base_fields() {
+    let mut metadata = Metadata::new();
+    metadata.insert("k".into(), "v".into());
+    let base = CompressOptions::default().with_metadata(metadata);
+    let fast = Level::Fast.apply(&base);
+    assert!(!fast.enable_value_pool);
+    assert_eq!(fast.metadata["k"], "v");
+
+    let tuned = compress_with_level(&json!({"a": 1}), Level::Max, &base).unwrap();
+    assert_eq!(tuned.options.metadata["k"], "v");
+    assert_eq!(json_packer::read_metadata(&tuned.bytes).unwrap()["k"], "v");
+}
+
+#[test]
+fn default_is_never_larger_than_fast() {
+    let docs = [
+        // 无重复字符串：值池为空时不写值池区，字符串也不带引用位
+        json!({"a": "x", "b": "y", "c": ["p", "q", "r", "s", "t"]}),
+        json!(["one", "two", "three"]),
+        json!({}),
+        doc(),
+    ];
+    let base = CompressOptions::default();
+    for v in &docs {
+        let fast = compress_with_level(v, Level::Fast, &base).unwrap().bytes;
+        let default = compress_with_level(v, Level::Default, &base).unwrap().bytes;
+        assert!(default.len() <= fast.len(), "{v}: {} > {}", default.len(), fast.len());
+        assert_eq!(&decompress_from_bytes(&default).unwrap(), v);
+    }
+    let v = &docs[0];
+    assert_eq!(compress_with_level(v, Level::Default, &base).unwrap().bytes, compress_with_level(v, Level::Fast, &base).unwrap().bytes);
+}
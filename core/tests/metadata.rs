@@ -0,0 +1,41 @@
+use json_packer::{compress_to_bytes, decompress_from_bytes, read_metadata, CompressOptions, Metadata, VERSION_V1, VERSION_V3};
+use serde_json::json;
+
+fn sample_metadata() -> Metadata {
+    let mut m = Metadata::new();
+    m.insert("content-type".to_string(), "application/json".to_string());
+    m.insert("schema-version".to_string(), "3".to_string());
+    m.insert("producer".to_string(), "ingest-service".to_string());
+    m
+}
+
+#[test]
+fn metadata_roundtrip_and_header_only_read() {
+    let v = json!({"id": 7, "name": "Alice"});
+    let opt = CompressOptions::default().with_metadata(sample_metadata());
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(bytes[4], VERSION_V3);
+    assert_eq!(read_metadata(&bytes).unwrap(), sample_metadata());
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+
+    // 只需包头即可读取元数据：截断数据区不影响
+    let header_only = &bytes[..bytes.len() - 4];
+    assert_eq!(read_metadata(header_only).unwrap(), sample_metadata());
+}
+
+#[test]
+fn metadata_with_value_pool() {
+    let v = json!(["connected", "connected", "connected", {"s": "connected"}]);
+    let opt = CompressOptions::default().with_value_pool(true).with_metadata(sample_metadata());
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(read_metadata(&bytes).unwrap().len(), 3);
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+}
+
+#[test]
+fn no_metadata_keeps_v1_output() {
+    let v = json!({"a": 1});
+    let bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    assert_eq!(bytes[4], VERSION_V1);
+    assert!(read_metadata(&bytes).unwrap().is_empty());
+}
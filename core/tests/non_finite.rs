@@ -0,0 +1,111 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{decode_with_visitor, decompress_from_bytes, decompress_projected, decompress_with_options, disassemble, CompressOptions, DecodeOptions, Decompressor, Error, NonFinite, PackWriter, Visitor};
+use serde_json::json;
+
+fn non_finite() -> CompressOptions {
+    CompressOptions::default().with_non_finite_floats(true)
+}
+
+/// 依次收集浮点数的位模式
+#[derive(Default)]
+struct Floats(Vec<u64>);
+
+impl Visitor for Floats {
+    fn value_float(&mut self, value: f64) { self.0.push(value.to_bits()); }
+}
+
+fn pack(opt: CompressOptions, floats: &[f64]) -> Result<Vec<u8>, Error> {
+    let mut w = PackWriter::new(opt);
+    w.begin_object()?;
+    w.key("xs")?;
+    w.begin_array()?;
+    for &f in floats { w.float(f)?; }
+    w.end_array()?;
+    w.key("x")?;
+    w.float(floats[0])?;
+    w.end_object()?;
+    w.finish()
+}
+
+#[test]
+fn non_finite_floats_restore_by_policy() {
+    let floats = [f64::NAN, f64::INFINITY, -0.5, f64::NEG_INFINITY];
+    let bytes = pack(non_finite(), &floats).unwrap();
+
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!({"xs": ["NaN", "Infinity", -0.5, "-Infinity"], "x": "NaN"}));
+    let null = DecodeOptions::default().with_non_finite(NonFinite::Null);
+    assert_eq!(decompress_with_options(&bytes, &null).unwrap(), json!({"xs": [null, null, -0.5, null], "x": null}));
+    let custom = DecodeOptions::default().with_non_finite(NonFinite::Custom(|f| json!({"$float": f.to_string()})));
+    assert_eq!(decompress_with_options(&bytes, &custom).unwrap()["xs"][1], json!({"$float": "inf"}));
+
+    // 事件式解码收到原值（成员按键排序："x" 在前）
+    let mut seen = Floats::default();
+    decode_with_visitor(&bytes, &mut seen).unwrap();
+    assert_eq!(seen.0[1..], floats.map(f64::to_bits));
+
+    // 定型数组与投影同样适用
+    let typed = pack(non_finite().with_typed_arrays(true), &floats).unwrap();
+    assert!(disassemble(&typed).to_string().contains("float NaN"));
+    assert_eq!(decompress_projected(&typed, &["/xs/3"]).unwrap(), json!({"xs": ["-Infinity"]}));
+}
+
+#[test]
+fn projection_follows_representation_policy() {
+    let null = Decompressor::new(DecodeOptions::default().with_non_finite(NonFinite::Null));
+    let custom = Decompressor::new(DecodeOptions::default().with_non_finite(NonFinite::Custom(|f| json!({"$float": f.to_string()}))));
+    for typed in [false, true] {
+        let bytes = pack(non_finite().with_typed_arrays(typed), &[f64::NAN, 1.5, f64::NEG_INFINITY]).unwrap();
+        // 完整匹配的子树与逐元素投影两条路径
+        assert_eq!(null.decompress_projected(&bytes, &["/xs"]).unwrap(), json!({"xs": [null, 1.5, null]}));
+        assert_eq!(null.decompress_projected(&bytes, &["/xs/0", "/x"]).unwrap(), json!({"xs": [null], "x": null}));
+        assert_eq!(custom.decompress_projected(&bytes, &["/xs/2"]).unwrap(), json!({"xs": [{"$float": "-inf"}]}));
+        assert_eq!(custom.decompress_projected(&bytes, &["/x"]).unwrap(), json!({"x": {"$float": "NaN"}}));
+    }
+}
+
+#[test]
+fn non_finite_floats_require_opt_in() {
+    assert!(matches!(pack(CompressOptions::default(), &[f64::NAN]), Err(Error::IllegalFloat)));
+}
+
+#[test]
+fn nan_payloads_and_signed_zero_reach_visitor_bit_exact() {
+    // 负号 NaN、带载荷的 signaling NaN、-0.0、最小次正规数
+    let bits = [0xfff8_0000_0000_0000, 0x7ff0_0000_0000_0001, 0x7ff8_dead_beef_0001, (-0.0f64).to_bits(), 1, f64::NEG_INFINITY.to_bits()];
+    let floats = bits.map(f64::from_bits);
+    for typed in [false, true] {
+        let bytes = pack(non_finite().with_typed_arrays(typed), &floats).unwrap();
+        let mut seen = Floats::default();
+        decode_with_visitor(&bytes, &mut seen).unwrap();
+        assert_eq!(seen.0[1..], bits, "typed={typed}");
+        assert_eq!(decompress_from_bytes(&bytes).unwrap()["xs"], json!(["NaN", "NaN", "NaN", -0.0, 5e-324, "-Infinity"]));
+    }
+}
+
+/// 手工构造：根为原值写法的单个浮点数
+fn float_package(fl: u64, f: f64) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: fl, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::FLOAT as u64, 3);
+    w.write_bits(f.to_bits(), 64);
+    w.into_bytes()
+}
+
+#[test]
+fn header_flag_gates_non_finite_payloads() {
+    for f in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let bytes = float_package(0, f);
+        assert_eq!(decompress_from_bytes(&bytes).unwrap_err().code(), "illegal_float");
+        assert_eq!(decompress_projected(&bytes, &[""]).unwrap_err().code(), "illegal_float");
+        assert!(decode_with_visitor(&bytes, &mut Floats::default()).is_err());
+        assert!(disassemble(&bytes).error.is_some());
+
+        let mut seen = Floats::default();
+        decode_with_visitor(&float_package(flags::NON_FINITE, f), &mut seen).unwrap();
+        assert_eq!(seen.0, [f.to_bits()]);
+    }
+    // 置位但不含非有限值的包照常解码
+    assert_eq!(decompress_from_bytes(&float_package(flags::NON_FINITE, 2.5)).unwrap(), json!(2.5));
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use json_packer::{compress_to_bytes, CompressOptions, Compressor, Error, Extension, FloatPrecision, PackWriter, PathOptions, RedactRule, SharedDictionary};
+use proptest::prelude::*;
+use serde_json::json;
+
+fn all_features() -> CompressOptions {
+    CompressOptions::default().with_value_pool(true).with_huffman_pool_refs(true).with_value_dicts(true)
+}
+
+#[test]
+fn events_match_equivalent_value() {
+    // 键乱序且含重复键：等价 Value 按 Map 顺序排列，重复键保留最后一次
+    let mut w = PackWriter::new(all_features());
+    w.begin_array().unwrap();
+    for i in 0..6 {
+        w.begin_object().unwrap();
+        w.key("status").unwrap();
+        w.string(if i % 2 == 0 { "ok" } else { "failed" }).unwrap();
+        w.key("id").unwrap();
+        w.uint(i).unwrap();
+        w.key("host").unwrap();
+        w.string("draft").unwrap();
+        w.key("host").unwrap();
+        w.string("server-01.example.com").unwrap();
+        w.key("tags").unwrap();
+        w.value(&json!(["a", 1.5, null, true])).unwrap();
+        w.end_object().unwrap();
+    }
+    w.end_array().unwrap();
+    let bytes = w.finish().unwrap();
+
+    let items: Vec<_> = (0..6)
+        .map(|i| json!({"status": if i % 2 == 0 { "ok" } else { "failed" }, "id": i, "host": "server-01.example.com", "tags": ["a", 1.5, null, true]}))
+        .collect();
+    let v = json!(items);
+    assert_eq!(bytes, compress_to_bytes(&v, &all_features()).unwrap());
+    assert_eq!(json_packer::decompress_from_bytes(&bytes).unwrap(), v);
+}
+
+#[test]
+fn shared_dictionary_and_fallback() {
+    let samples = [json!({"name": "alice", "role": "admin"})];
+    let dict = SharedDictionary::from_samples(&samples, Some(Default::default())).unwrap();
+    let opts = all_features();
+    for v in [json!({"role": "admin", "name": "bob"}), json!({"other": "x"})] {
+        let mut w = PackWriter::with_dictionary(opts.clone(), dict.clone());
+        w.value(&v).unwrap();
+        let expected = Compressor::with_dictionary(opts.clone(), dict.clone()).compress(&v).unwrap();
+        assert_eq!(w.finish().unwrap(), expected);
+    }
+}
+
+#[test]
+fn misuse_is_reported() {
+    let mut w = PackWriter::new(CompressOptions::default());
+    assert!(matches!(w.key("a"), Err(Error::WriterState(_))));
+    w.begin_object().unwrap();
+    assert!(matches!(w.int(1), Err(Error::WriterState(_))));
+    w.key("a").unwrap();
+    assert!(matches!(w.end_object(), Err(Error::WriterState(_))));
+    assert!(matches!(w.float(f64::NAN), Err(Error::IllegalFloat)));
+    assert!(matches!(w.end_array(), Err(Error::WriterState(_))));
+    w.int(1).unwrap();
+    w.end_object().unwrap();
+    assert!(matches!(w.null(), Err(Error::WriterState(_))));
+
+    let mut open = PackWriter::new(CompressOptions::default());
+    open.begin_array().unwrap();
+    let err = open.finish().unwrap_err();
+    assert_eq!(err.code(), "writer_state");
+}
+
+#[test]
+fn options_needing_the_whole_document_are_rejected() {
+    let dict = SharedDictionary::from_samples([&json!({"a": 1})], None).unwrap();
+    for opts in [
+        CompressOptions::default().with_redact(vec![RedactRule::drop("/a")]),
+        CompressOptions::default().with_path_options(vec![PathOptions::new("/a")]),
+        CompressOptions::default().with_float_precision(Some(FloatPrecision::Decimals(2))),
+        CompressOptions::default().with_extensions(vec![Arc::new(Never)]),
+        CompressOptions::default().with_subtree_pool(true),
+    ] {
+        // 写入根值时即拒绝，不等到 finish
+        let mut w = PackWriter::new(opts.clone());
+        assert!(matches!(w.begin_object(), Err(Error::WriterState(_))), "{opts:?}");
+        assert!(matches!(w.finish(), Err(Error::WriterState(_))));
+        let mut w = PackWriter::with_dictionary(opts.clone(), dict.clone());
+        assert_eq!(w.value(&json!({"a": 1})).unwrap_err().code(), "writer_state", "{opts:?}");
+    }
+}
+
+#[derive(Debug)]
+struct Never;
+
+impl Extension for Never {
+    fn id(&self) -> u64 { 1 }
+    fn encode(&self, _: &serde_json::Value) -> Option<Vec<u8>> { None }
+    fn decode(&self, _: &[u8]) -> Result<serde_json::Value, Error> { Err(Error::InvalidExtension(1)) }
+}
+
+fn arb_json() -> impl Strategy<Value = serde_json::Value> {
+    let leaf = prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::Bool),
+        any::<i64>().prop_map(|x| json!(x)),
+        any::<u64>().prop_map(|x| json!(x)),
+        any::<f64>().prop_filter("finite", |f| f.is_finite()).prop_map(|f| json!(f)),
+        // 小字母表，制造重复字符串
+        "[ab]{0,6}".prop_map(serde_json::Value::String),
+    ];
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(serde_json::Value::Array),
+            prop::collection::hash_map("[a-d]{1,2}", inner, 0..6).prop_map(|m| serde_json::Value::Object(m.into_iter().collect())),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn prop_pack_writer_matches_compress(v in arb_json(), pool in any::<bool>(), refs in any::<bool>(), dicts in any::<bool>()) {
+        let opts = CompressOptions::default()
+            .with_value_pool(pool)
+            .with_huffman_pool_refs(refs)
+            .with_value_dicts(dicts);
+        let mut w = PackWriter::new(opts.clone());
+        w.value(&v).unwrap();
+        prop_assert_eq!(w.finish().unwrap(), compress_to_bytes(&v, &opts).unwrap());
+    }
+}
@@ -0,0 +1,95 @@
+use json_packer::{compress_to_bytes, decompress_from_bytes, inspect, CompressOptions, PathOptions, StringMode};
+use serde_json::{json, Value};
+
+/// 同一字符串在 `keys` 各键下各重复 4 次
+fn repeated(keys: &[&str], s: &str) -> Value {
+    Value::Object(keys.iter().map(|k| (k.to_string(), json!([s, s, s, s]))).collect())
+}
+
+fn pool_of(v: &Value, opt: &CompressOptions) -> Vec<Value> {
+    let bytes = compress_to_bytes(v, opt).unwrap();
+    assert_eq!(&decompress_from_bytes(&bytes).unwrap(), v);
+    inspect(&bytes).unwrap().pool
+}
+
+fn pooled() -> CompressOptions {
+    CompressOptions::default().with_value_pool(true)
+}
+
+#[test]
+fn first_rule_wins_at_the_same_position() {
+    let v = json!({"keep": vec!["repeated-value"; 4], "drop": vec!["another-value"; 4]});
+    // 两条规则都匹配 /keep/*：取靠前的一条
+    let opt = pooled().with_path_options(vec![
+        PathOptions { pool: Some(true), ..PathOptions::new("/keep/*") },
+        PathOptions { pool: Some(false), ..PathOptions::new("/**") },
+    ]);
+    assert_eq!(pool_of(&v, &opt), [json!("repeated-value")]);
+    let opt = pooled().with_path_options(vec![
+        PathOptions { pool: Some(false), ..PathOptions::new("/**") },
+        PathOptions { pool: Some(true), ..PathOptions::new("/keep/*") },
+    ]);
+    assert!(pool_of(&v, &opt).is_empty());
+}
+
+#[test]
+fn deeper_match_replaces_the_container_rule() {
+    let v = json!({"a": {"b": vec!["repeated-value"; 4], "c": vec!["another-value"; 4]}});
+    // /a/b 的规则未设置 pool：回落到全局选项，而不是继承 /a 的规则
+    let opt = pooled().with_path_options(vec![
+        PathOptions { pool: Some(false), ..PathOptions::new("/a") },
+        PathOptions { pool_min_string_len: Some(0), ..PathOptions::new("/a/b") },
+    ]);
+    assert_eq!(pool_of(&v, &opt), [json!("repeated-value")]);
+    // 全局关闭时只有显式开启的位置入池
+    let opt = CompressOptions::default().with_path_options(vec![PathOptions { pool: Some(true), ..PathOptions::new("/a/c/*") }]);
+    assert_eq!(pool_of(&v, &opt), [json!("another-value")]);
+}
+
+#[test]
+fn min_string_len_is_overridden_per_path() {
+    let v = repeated(&["code", "name"], "E1");
+    let opt = pooled().with_pool_min_string_len(8).with_path_options(vec![PathOptions { pool_min_string_len: Some(0), ..PathOptions::new("/code") }]);
+    // 同一字符串只在放宽长度的位置计数，其余位置内联
+    assert_eq!(pool_of(&v, &opt), [json!("E1")]);
+    assert!(pool_of(&v, &pooled().with_pool_min_string_len(8)).is_empty());
+}
+
+#[test]
+fn escaped_and_wildcard_segments_match_keys() {
+    let v = repeated(&["a/b", "c~d", "plain"], "shared-string-value");
+    let only = |path: &str| pooled().with_path_options(vec![
+        PathOptions { pool: Some(false), ..PathOptions::new("/*") },
+        PathOptions { pool: Some(true), ..PathOptions::new(path) },
+    ]);
+    // 外层 "/*" 先匹配容器，路径规则须匹配到元素才能覆盖
+    assert!(pool_of(&v, &only("/a~1b")).is_empty());
+    assert_eq!(pool_of(&v, &only("/a~1b/*")), [json!("shared-string-value")]);
+    assert_eq!(pool_of(&v, &only("/c~0d/**")), [json!("shared-string-value")]);
+}
+
+#[test]
+fn inline_mode_disables_pool_and_value_dict() {
+    let v = json!({"items": [{"level": "warn"}, {"level": "info"}, {"level": "warn"}, {"level": "info"}], "other": vec!["long-shared-value"; 3]});
+    let base = pooled().with_value_dicts(true);
+    let opt = base.clone().with_path_options(vec![PathOptions { string_mode: Some(StringMode::Inline), ..PathOptions::new("/items") }]);
+    let auto = compress_to_bytes(&v, &base).unwrap();
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    assert!(inspect(&auto).unwrap().value_dicts.bit_len > 0);
+    assert_eq!(inspect(&bytes).unwrap().value_dicts.bit_len, 0);
+    // 规则之外的位置不受影响
+    assert_eq!(pool_of(&v, &opt), [json!("long-shared-value")]);
+}
+
+#[test]
+fn malformed_paths_are_rejected() {
+    let v = json!({"items": []});
+    for path in ["items", "/items/01", "/items/+1"] {
+        let opt = CompressOptions::default().with_path_options(vec![PathOptions::new(path)]);
+        assert_eq!(compress_to_bytes(&v, &opt).unwrap_err().code(), "invalid_pattern", "{path}");
+    }
+    // "-0" 不是数字段，按普通键匹配
+    let opt = CompressOptions::default().with_path_options(vec![PathOptions::new("/items/-0")]);
+    assert!(compress_to_bytes(&v, &opt).is_ok());
+}
@@ -0,0 +1,104 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{
+    compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, CompressOptions, Compressor, DecodeOptions, Decompressor,
+    SharedDictionary,
+};
+use serde_json::{json, Value};
+
+fn coded() -> CompressOptions {
+    CompressOptions::default().with_value_pool(true).with_pool_min_repeats(2).with_huffman_pool_refs(true)
+}
+
+/// 反汇编清单中各池条目的码长
+fn ref_lens(bytes: &[u8]) -> Vec<u8> {
+    let dump = disassemble(bytes);
+    assert!(dump.error.is_none());
+    dump.lines.iter().filter_map(|l| l.text.strip_prefix("pool.ref_len[")?.split_once("] = ")?.1.parse().ok()).collect()
+}
+
+#[test]
+fn code_lengths_follow_reference_counts() {
+    // 引用次数 8 : 4 : 2 : 2
+    let mut items = Vec::new();
+    for (s, n) in [("most-frequent-value", 8), ("second-value-here", 4), ("third-value-here", 2), ("fourth-value-here", 2)] {
+        items.extend(std::iter::repeat_n(json!(s), n));
+    }
+    let v = Value::Array(items);
+    let bytes = compress_to_bytes(&v, &coded()).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    assert_eq!(ref_lens(&bytes), [1, 2, 3, 3]);
+    assert_eq!(decompress_projected(&bytes, &["/9", "/15"]).unwrap(), json!(["second-value-here", "fourth-value-here"]));
+}
+
+#[test]
+fn single_referenced_entry_gets_a_one_bit_code() {
+    let v = json!(vec!["only-pooled-string"; 3]);
+    let bytes = compress_to_bytes(&v, &coded()).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    assert_eq!(ref_lens(&bytes), [1]);
+}
+
+#[test]
+fn huffman_ref_ids_with_subtrees_and_shared_dictionary() {
+    let v = json!({"a": [{"x": 1, "y": 2}, {"x": 1, "y": 2}, {"x": 1, "y": 2}], "s": ["connected", "connected", "connected"]});
+    let opt = coded().with_subtree_pool(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+
+    let dict = SharedDictionary::from_samples([&v], Some(Default::default())).unwrap();
+    let mut c = Compressor::with_dictionary(opt, dict.clone());
+    let d = Decompressor::with_dictionary(DecodeOptions::default().with_strict(true), dict);
+    let packed = c.compress(&v).unwrap();
+    assert_eq!(d.decompress(&packed).unwrap(), v);
+}
+
+#[test]
+fn no_pool_references_keep_legacy_format() {
+    let v = json!({"a": "unique-one", "b": "unique-two"});
+    let opt = CompressOptions::default().with_huffman_pool_refs(true);
+    assert_eq!(compress_to_bytes(&v, &opt).unwrap(), compress_to_bytes(&v, &CompressOptions::default()).unwrap());
+}
+
+/// 手工构造：值池为 `entries` 个字符串条目，码长表为 `lengths`，根值为一个池引用，码字由 `code` 写入
+fn ref_package(pool_len: u64, entries: u64, lengths: &[u64], code: impl FnOnce(&mut BitWriter)) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::VALUE_POOL | flags::POOL_REF_CODES, ..PackageHeader::new(0, pool_len) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    for i in 0..entries {
+        w.write_bits(tag::STRING as u64, 3);
+        write_uleb128(&mut w, 1);
+        w.write_byte(b'a' + i as u8);
+    }
+    for &len in lengths { write_uleb128(&mut w, len); }
+    w.write_bits(tag::STRING as u64, 3);
+    w.write_bits(1, 1);
+    code(&mut w);
+    w.into_bytes()
+}
+
+#[test]
+fn malformed_ref_code_tables_are_data_errors() {
+    // 码长 [1, 2, 2]：码字 0、10、11（LSB 先写）
+    assert_eq!(decompress_from_bytes(&ref_package(3, 3, &[1, 2, 2], |w| w.write_bits(0b11, 2))).unwrap(), json!("c"));
+
+    let cases = [
+        // 超出 u8、超过 63 位、违反 Kraft 不等式
+        ref_package(2, 2, &[256, 1], |w| w.write_bits(0, 1)),
+        ref_package(2, 2, &[64, 1], |w| w.write_bits(0, 1)),
+        ref_package(3, 3, &[1, 1, 1], |w| w.write_bits(0, 1)),
+        // 不完整码表中读到未分配的码字
+        ref_package(2, 2, &[2, 0], |w| w.write_bits(0b11, 2)),
+        // 全部条目都没有码字时仍出现引用
+        ref_package(2, 2, &[0, 0], |w| w.write_bits(0, 8)),
+    ];
+    for bytes in &cases {
+        assert_eq!(decompress_from_bytes(bytes).unwrap_err().code(), "huffman_error");
+        assert!(decompress_projected(bytes, &[""]).is_err());
+        assert!(disassemble(bytes).error.is_some());
+    }
+
+    // 包头声明的条目数远超剩余数据：值池与码长表都不得按声明数预分配
+    let huge = ref_package(u64::MAX >> 1, 0, &[], |_| {});
+    assert_eq!(decompress_from_bytes(&huge).unwrap_err().code(), "truncated_data");
+}
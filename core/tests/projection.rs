@@ -0,0 +1,68 @@
+use json_packer::{compress_to_bytes, decompress_projected, CompressOptions, Decompressor, SharedDictionary};
+use serde_json::json;
+
+fn all_features() -> CompressOptions {
+    CompressOptions::default()
+        .with_value_pool(true)
+        .with_subtree_pool(true)
+        .with_pool_min_subtree_nodes(1)
+        .with_huffman_pool_refs(true)
+        .with_value_dicts(true)
+}
+
+fn sample() -> serde_json::Value {
+    let addr = json!({"street": "1 Main St", "city": "Springfield"});
+    let items: Vec<_> = (0..6)
+        .map(|i| json!({"id": i, "name": format!("item-{i}"), "status": if i % 2 == 0 { "ok" } else { "failed" }, "addr": addr, "tags": ["a", "b"]}))
+        .collect();
+    json!({"items": items, "meta": {"total": 6, "next": null, "a/b": "slash"}})
+}
+
+#[test]
+fn wildcard_and_pointer_paths() {
+    let v = sample();
+    for opt in [CompressOptions::default(), all_features()] {
+        let bytes = compress_to_bytes(&v, &opt).unwrap();
+        let names: Vec<_> = (0..6).map(|i| json!({"name": format!("item-{i}")})).collect();
+        assert_eq!(decompress_projected(&bytes, &["/items/*/name"]).unwrap(), json!({"items": names}));
+        assert_eq!(
+            decompress_projected(&bytes, &["/meta/total", "/meta/a~1b", "/items/1/addr/city"]).unwrap(),
+            json!({"items": [{"addr": {"city": "Springfield"}}], "meta": {"total": 6, "a/b": "slash"}})
+        );
+        // `**` 匹配任意深度（含零段）
+        let cities: Vec<_> = (0..6).map(|_| json!({"addr": {"city": "Springfield"}})).collect();
+        assert_eq!(decompress_projected(&bytes, &["/**/city"]).unwrap(), json!({"items": cities}));
+        assert_eq!(decompress_projected(&bytes, &["/**"]).unwrap(), v);
+        assert_eq!(decompress_projected(&bytes, &[""]).unwrap(), v);
+        assert_eq!(decompress_projected(&bytes, &["/missing"]).unwrap(), json!(null));
+        assert_eq!(decompress_projected(&bytes, &["/items/*/status"]).unwrap()["items"][1]["status"], "failed");
+    }
+}
+
+#[test]
+fn shared_dictionary_and_invalid_pattern() {
+    let v = sample();
+    let dict = SharedDictionary::from_samples(std::slice::from_ref(&v), Some(Default::default())).unwrap();
+    let bytes = json_packer::Compressor::with_dictionary(all_features(), dict.clone()).compress(&v).unwrap();
+    let d = Decompressor::with_dictionary(Default::default(), dict);
+    assert_eq!(d.decompress_projected(&bytes, &["/meta/next"]).unwrap(), json!({"meta": {"next": null}}));
+
+    let err = decompress_projected(&bytes, &["items"]).unwrap_err();
+    assert_eq!(err.code(), "invalid_pattern");
+}
+
+#[test]
+fn index_segments_must_be_canonical() {
+    let v = json!({"items": ["a", "b", "c"], "codes": {"0": "zero", "007": "bond"}});
+    let bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    assert_eq!(decompress_projected(&bytes, &["/items/0", "/items/2"]).unwrap(), json!({"items": ["a", "c"]}));
+    assert_eq!(decompress_projected(&bytes, &["/codes/0"]).unwrap(), json!({"codes": {"0": "zero"}}));
+    // RFC 6901：数组下标不带符号、不带前导零
+    for bad in ["/items/+1", "/items/01", "/items/00", "/codes/007", "/**/+0"] {
+        let err = decompress_projected(&bytes, &[bad]).unwrap_err();
+        assert!(matches!(err.kind(), json_packer::Error::InvalidPattern(p) if p == bad), "{bad}");
+    }
+    // 脱敏与路径选项共用同一解析
+    let opt = CompressOptions::default().with_redact(vec![json_packer::RedactRule::drop("/items/01")]);
+    assert!(matches!(compress_to_bytes(&v, &opt), Err(json_packer::Error::InvalidPattern(_))));
+}
@@ -34,4 +34,19 @@ proptest! {
         let out = decompress_from_bytes(&bytes).unwrap();
         prop_assert_eq!(v, out);
     }
+
+    #[test]
+    fn prop_roundtrip_subtree_pool(v in arb_json()) {
+        // 人为制造重复子树，覆盖嵌套引用与字符串池共存
+        let doc = serde_json::json!({"a": [v.clone(), v.clone()], "b": v});
+        let opt = CompressOptions::default()
+            .with_value_pool(true)
+            .with_subtree_pool(true)
+            .with_pool_min_subtree_nodes(1)
+            .with_huffman_pool_refs(true)
+            .with_value_dicts(true);
+        let bytes = compress_to_bytes(&doc, &opt).unwrap();
+        let out = decompress_from_bytes(&bytes).unwrap();
+        prop_assert_eq!(doc, out);
+    }
 }
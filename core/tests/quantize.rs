@@ -0,0 +1,131 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, inspect, CompressOptions, Error, FloatPrecision, PathOptions};
+use serde_json::{json, Value};
+
+fn quantized(v: &Value, precision: FloatPrecision) -> Value {
+    let opt = CompressOptions::default().with_float_precision(Some(precision));
+    decompress_from_bytes(&compress_to_bytes(v, &opt).unwrap()).unwrap()
+}
+
+#[test]
+fn values_at_precision_boundary_round_half_away() {
+    // 0.125 与 0.375 可精确表示，恰在半步上
+    let v = json!([0.125, -0.125, 0.375, 0.0049, 0.005, 1e-3, -0.001]);
+    assert_eq!(quantized(&v, FloatPrecision::Decimals(2)), json!([0.13, -0.13, 0.38, 0.0, 0.01, 0.0, -0.0]));
+}
+
+#[test]
+fn significant_digits_scale_with_magnitude() {
+    // 9.995 的二进制值略小于 9.995，9.9951 进位后多出一位
+    let v = json!([123456.789, 0.000123456, -98.76, 0.0, 9.995, 9.9951]);
+    assert_eq!(quantized(&v, FloatPrecision::Significant(3)), json!([123000.0, 0.000123, -98.8, 0.0, 9.99, 10.0]));
+    // 0 位有效数字按 1 位处理
+    assert_eq!(quantized(&json!([4567.0]), FloatPrecision::Significant(0)), json!([5000.0]));
+}
+
+#[test]
+fn overflowing_or_integral_values_are_kept() {
+    // 舍入结果溢出为 Inf 时保留原值；整数不受精度影响
+    let v = json!([f64::MAX, -f64::MAX, 12345678901234567u64, -7]);
+    assert_eq!(quantized(&v, FloatPrecision::Significant(3)), v);
+    assert_eq!(quantized(&json!([1e300]), FloatPrecision::Decimals(255)), json!([1e300]));
+    // 低于精度的次正规数归零
+    assert_eq!(quantized(&json!([5e-324]), FloatPrecision::Decimals(9)), json!([0.0]));
+}
+
+#[test]
+fn values_without_short_decimal_form_stay_bit_exact() {
+    // 最短十进制写法超过 64 位时退回原值写法
+    let v = json!([1.0 / 3.0, std::f64::consts::PI, 0.1 + 0.2, -1.0 / 7.0]);
+    assert_eq!(quantized(&v, FloatPrecision::Decimals(255)), v);
+}
+
+#[test]
+fn deeper_path_precision_wins_and_rules_without_precision_inherit() {
+    let v = json!({"a": {"b": 1.23456, "c": 1.23456}, "d": 1.23456});
+    let opt = CompressOptions::default()
+        .with_float_precision(Some(FloatPrecision::Decimals(1)))
+        .with_path_options(vec![
+            PathOptions { float_precision: Some(FloatPrecision::Decimals(3)), ..PathOptions::new("/a") },
+            PathOptions { float_precision: Some(FloatPrecision::Decimals(4)), ..PathOptions::new("/a/c") },
+            // 未设置精度：/a/b 沿用 /a 的精度，/d 沿用全局精度
+            PathOptions::new("/a/b"),
+            PathOptions::new("/d"),
+        ]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!({"a": {"b": 1.235, "c": 1.2346}, "d": 1.2}));
+}
+
+#[test]
+fn path_only_precision_marks_package_lossy() {
+    let v = json!({"x": [0.123, 0.456], "y": 0.789});
+    let opt = CompressOptions::default().with_path_options(vec![PathOptions { float_precision: Some(FloatPrecision::Decimals(1)), ..PathOptions::new("/x/1") }]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!({"x": [0.123, 0.5], "y": 0.789}));
+    assert!(inspect(&bytes).unwrap().is_lossy());
+    assert!(disassemble(&bytes).to_string().contains("lossy"));
+    assert_eq!(decompress_projected(&bytes, &["/y"]).unwrap(), json!({"y": 0.789}));
+    assert!(!inspect(&compress_to_bytes(&v, &CompressOptions::default()).unwrap()).unwrap().is_lossy());
+}
+
+/// 手工构造 QUANTIZED_FLOATS 包：空字典，根值为浮点数，`payload` 写入 FLOAT 标签之后的内容
+fn float_package(payload: impl FnOnce(&mut BitWriter)) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::QUANTIZED_FLOATS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::FLOAT as u64, 3);
+    payload(&mut w);
+    w.into_bytes()
+}
+
+/// 十进制形式 `m × 10^exp`
+fn decimal_package(m: i64, exp: i64) -> Vec<u8> {
+    float_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits((m < 0) as u64, 1);
+        write_uleb128(w, m.unsigned_abs());
+        w.write_bits((exp < 0) as u64, 1);
+        write_uleb128(w, exp.unsigned_abs());
+    })
+}
+
+#[test]
+fn malformed_decimal_exponents_are_data_errors() {
+    assert_eq!(decompress_from_bytes(&decimal_package(15, -1)).unwrap(), json!(1.5));
+    assert_eq!(decompress_from_bytes(&decimal_package(-15, -1)).unwrap(), json!(-1.5));
+    assert_eq!(decompress_from_bytes(&decimal_package(1, -400)).unwrap(), json!(0.0));
+    // 超出 i32、超出指数界、还原溢出为 ±Inf
+    for (m, exp) in [(1, 1 << 40), (1, -(1 << 40)), (1, 401), (1, 400), (9, 308), (-9, 308)] {
+        let bytes = decimal_package(m, exp);
+        let err = decompress_from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err.kind(), Error::InvalidQuantizedFloat), "{m}e{exp}: {err}");
+        assert_eq!(err.code(), "invalid_quantized_float");
+        assert!(decompress_projected(&bytes, &[""]).is_err());
+    }
+}
+
+#[test]
+fn malformed_float_payloads_are_data_errors() {
+    // 尾数超出 i64
+    let huge_mantissa = float_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits(0, 1);
+        write_uleb128(w, u64::MAX);
+        w.write_bits(0, 1);
+        write_uleb128(w, 0);
+    });
+    assert_eq!(decompress_from_bytes(&huge_mantissa).unwrap_err().code(), "varint_error");
+    // 原值写法只剩 32 位
+    let truncated_raw = float_package(|w| {
+        w.write_bits(0, 1);
+        w.write_bits(0, 32);
+    });
+    assert_eq!(decompress_from_bytes(&truncated_raw).unwrap_err().code(), "truncated_data");
+    // 原值写法为 NaN 但包头未声明非有限值
+    let raw_nan = float_package(|w| {
+        w.write_bits(0, 1);
+        w.write_bits(f64::NAN.to_bits(), 64);
+    });
+    assert_eq!(decompress_from_bytes(&raw_nan).unwrap_err().code(), "illegal_float");
+}
@@ -0,0 +1,153 @@
+use json_packer::{compress_to_bytes, compress_with_report, decompress_from_bytes, estimate_size, inspect, CompressOptions, Compressor, Decompressor, PathOptions, RedactRule, SharedDictionary, StringMode};
+use serde_json::json;
+
+fn roundtrip(v: &serde_json::Value, rules: Vec<RedactRule>) -> (Vec<u8>, serde_json::Value) {
+    let opt = CompressOptions::default().with_value_pool(true).with_value_dicts(true).with_redact(rules);
+    let bytes = compress_to_bytes(v, &opt).unwrap();
+    let out = decompress_from_bytes(&bytes).unwrap();
+    (bytes, out)
+}
+
+fn leaks(bytes: &[u8], secret: &str) -> bool {
+    bytes.windows(secret.len()).any(|w| w == secret.as_bytes())
+}
+
+#[test]
+fn dropped_keys_and_values_never_reach_any_section() {
+    // 被移除的值若参与统计，会以高频字符串进入值池、以单一取值进入值字典
+    let v = json!([
+        {"id": 1, "secret": "hunter2-hunter2"},
+        {"id": 2, "secret": "hunter2-hunter2"},
+        {"id": 3, "secret": "hunter2-hunter2"},
+    ]);
+    let (bytes, out) = roundtrip(&v, vec![RedactRule::drop("/*/secret")]);
+    assert_eq!(out, json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    let info = inspect(&bytes).unwrap();
+    assert!(info.keys.iter().all(|k| k.key != "secret"));
+    assert!(info.pool.is_empty());
+    assert!(!leaks(&bytes, "hunter2"));
+}
+
+#[test]
+fn nulled_values_keep_their_key() {
+    let v = json!({"token": {"value": "t-1", "scope": "all"}, "user": "alice"});
+    let (bytes, out) = roundtrip(&v, vec![RedactRule::null("/token")]);
+    assert_eq!(out, json!({"token": null, "user": "alice"}));
+    let keys: Vec<_> = inspect(&bytes).unwrap().keys.into_iter().map(|k| k.key).collect();
+    assert!(keys.iter().any(|k| k == "token"));
+    assert!(!keys.iter().any(|k| k == "value" || k == "scope"));
+    assert!(!leaks(&bytes, "t-1"));
+}
+
+#[test]
+fn indices_refer_to_input_positions() {
+    // 移除 1 后，原下标 2 的元素不会前移成为新的 /items/1
+    let v = json!({"items": ["a", "b", "c", "d"]});
+    let (_, out) = roundtrip(&v, vec![RedactRule::drop("/items/1"), RedactRule::drop("/items/2")]);
+    assert_eq!(out, json!({"items": ["a", "d"]}));
+    // 路径规则同样按输入位置匹配，不受前面被移除的元素影响
+    let opt = CompressOptions::default()
+        .with_redact(vec![RedactRule::drop("/0")])
+        .with_path_options(vec![PathOptions { string_mode: Some(StringMode::Inline), ..PathOptions::new("/1") }]);
+    let v = json!(["dropped", "kept"]);
+    assert_eq!(decompress_from_bytes(&compress_to_bytes(&v, &opt).unwrap()).unwrap(), json!(["kept"]));
+}
+
+#[test]
+fn dropping_every_element_leaves_empty_containers() {
+    let v = json!({"list": [1, 2, 3], "map": {"a": 1, "b": 2}});
+    let (_, out) = roundtrip(&v, vec![RedactRule::drop("/list/*"), RedactRule::drop("/map/*")]);
+    assert_eq!(out, json!({"list": [], "map": {}}));
+}
+
+#[test]
+fn dropped_root_becomes_null_and_first_rule_wins() {
+    let v = json!({"api_key": "k", "other": 1});
+    assert_eq!(roundtrip(&v, vec![RedactRule::drop("/**")]).1, json!(null));
+    assert_eq!(roundtrip(&v, vec![RedactRule::null("/api_key"), RedactRule::drop("/*")]).1, json!({"api_key": null}));
+    // 外层先匹配时内层规则不再生效
+    assert_eq!(roundtrip(&v, vec![RedactRule::null("/**"), RedactRule::drop("/api_key")]).1, json!(null));
+}
+
+#[test]
+fn typed_arrays_see_redacted_elements() {
+    // 唯一的字符串被移除后，数组按类型化浮点写入
+    let v = json!([1.5, 2.5, "x", 3.5]);
+    let opt = CompressOptions::default().with_typed_arrays(true).with_redact(vec![RedactRule::drop("/2")]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!([1.5, 2.5, 3.5]));
+    let report = compress_with_report(&v, &opt).unwrap().1;
+    assert_eq!(report.floats.count, 3);
+    assert_eq!(report.strings.count, 0);
+}
+
+#[test]
+fn dropped_unknown_key_still_uses_shared_dictionary() {
+    let sample = json!({"name": "alice", "role": "admin"});
+    let dict = SharedDictionary::from_samples([&sample], None).unwrap();
+    let opt = CompressOptions::default().with_redact(vec![RedactRule::drop("/debug")]);
+    let v = json!({"name": "bob", "role": "user", "debug": {"trace": "..."}});
+    let bytes = Compressor::with_dictionary(opt, dict.clone()).compress(&v).unwrap();
+    // 脱敏后的键都在字典内，仍按预共享字典写出：不带字典无法解码
+    assert_eq!(decompress_from_bytes(&bytes).unwrap_err().code(), "shared_dictionary_required");
+    let out = Decompressor::with_dictionary(Default::default(), dict).decompress(&bytes).unwrap();
+    assert_eq!(out, json!({"name": "bob", "role": "user"}));
+}
+
+#[test]
+fn redacted_containers_are_not_deduplicated() {
+    let card = json!({"pan": "4111111111111111", "limits": [100, 200, 300, 400]});
+    let v = json!({"cards": [card, card, card]});
+    let opt = CompressOptions::default().with_subtree_pool(true).with_redact(vec![RedactRule::drop("/cards/*/pan")]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    let limits = json!([100, 200, 300, 400]);
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!({"cards": [{"limits": limits}, {"limits": limits}, {"limits": limits}]}));
+    // 卡片对象含脱敏位置，只有其内未脱敏的数组入池；原值不以池条目形式泄漏
+    assert_eq!(inspect(&bytes).unwrap().pool, vec![limits]);
+    assert!(!leaks(&bytes, "4111111111111111"));
+}
+
+#[test]
+fn estimate_and_report_apply_the_same_rules() {
+    let v = json!({"users": [{"name": "alice", "password": "p"}, {"name": "bob", "password": "p"}]});
+    let opt = CompressOptions::default().with_value_pool(true).with_redact(vec![RedactRule::drop("/users/*/password")]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(estimate_size(&v, &opt).unwrap().total_bytes(), bytes.len());
+    assert_eq!(compress_with_report(&v, &opt).unwrap().0, bytes);
+}
+
+#[test]
+fn malformed_rule_paths_are_rejected() {
+    for path in ["users", "/users/01", "/users/+1"] {
+        let opt = CompressOptions::default().with_redact(vec![RedactRule::drop(path)]);
+        assert_eq!(compress_to_bytes(&json!({"users": []}), &opt).unwrap_err().code(), "invalid_pattern", "{path}");
+    }
+}
+
+#[cfg(feature = "redact-hash")]
+#[test]
+fn hash_digests_strings_and_nulls_other_values() {
+    let v = json!({"name": "alice", "age": 30, "tags": ["a"]});
+    let (bytes, out) = roundtrip(&v, vec![RedactRule::hash("/name", ""), RedactRule::hash("/age", "s"), RedactRule::hash("/tags", "s")]);
+    // SHA-256("alice")
+    assert_eq!(out["name"], "2bd806c97f0e00af1a1fc3328fa763a9269723c8db8fac4f93af71db186d6e90");
+    assert_eq!(out["age"], json!(null));
+    assert_eq!(out["tags"], json!(null));
+    assert!(!leaks(&bytes, "alice"));
+}
+
+#[cfg(feature = "redact-hash")]
+#[test]
+fn hashed_values_are_pooled_by_digest() {
+    // 相同原值得到相同摘要，摘要照常参与值池统计；盐不同则摘要不同
+    let v = json!([{"email": "a@example.com"}, {"email": "a@example.com"}, {"email": "a@example.com"}]);
+    let opt = CompressOptions::default().with_value_pool(true).with_redact(vec![RedactRule::hash("/*/email", "pepper")]);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    let out = decompress_from_bytes(&bytes).unwrap();
+    let digest = out[0]["email"].as_str().unwrap();
+    assert_eq!(digest.len(), 64);
+    assert!(out.as_array().unwrap().iter().all(|u| u["email"] == digest));
+    assert_eq!(inspect(&bytes).unwrap().pool, vec![json!(digest)]);
+    let (_, salted) = roundtrip(&v, vec![RedactRule::hash("/*/email", "salt")]);
+    assert_ne!(salted[0]["email"], digest);
+}
@@ -0,0 +1,61 @@
+use json_packer::{compress_to_bytes, compress_with_report, inspect, CompressOptions, FloatPrecision};
+use serde_json::json;
+
+#[test]
+fn report_matches_output_and_counts_values() {
+    let v = json!([
+        {"name": "connected", "id": 1, "score": 1.5},
+        {"name": "connected", "id": 300, "score": 2.5},
+        {"name": "connected", "id": -2},
+    ]);
+    let opt = CompressOptions::default().with_value_pool(true);
+    let (bytes, report) = compress_with_report(&v, &opt).unwrap();
+    assert_eq!(bytes, compress_to_bytes(&v, &opt).unwrap());
+    assert_eq!(report.sizes.total_bytes(), bytes.len());
+    assert_eq!(report.sizes.body_bits, inspect(&bytes).unwrap().body.bit_len);
+
+    assert_eq!(report.keys, 3);
+    assert_eq!(report.key_refs, 8);
+    assert!(report.avg_key_code_len >= 1.0);
+
+    assert_eq!(report.pool_entries, 1);
+    assert_eq!(report.pool_hits, 3);
+    assert!(report.pool_bits_saved > 0);
+
+    assert_eq!(report.ints.count, 3);
+    // tag(3) + 符号位(1) + SLEB128（1 与 -2 各 2 字节，300 为 3 字节）
+    assert_eq!(report.ints.by_bits.get(&20), Some(&2));
+    assert_eq!(report.ints.by_bits.get(&28), Some(&1));
+    assert_eq!(report.floats.count, 2);
+    assert_eq!(report.floats.bits, 2 * 67);
+    assert_eq!(report.strings.count, 3);
+}
+
+#[test]
+fn report_without_pool_saves_nothing() {
+    let (_, report) = compress_with_report(&json!({"a": "x"}), &CompressOptions::default()).unwrap();
+    assert_eq!(report.pool_hits, 0);
+    assert_eq!(report.pool_bits_saved, 0);
+    assert_eq!(report.strings.by_bits.get(&(3 + 16)), Some(&1));
+}
+
+#[test]
+fn typed_array_elements_count_by_kind() {
+    let v = json!({"flags": [true, false, true, true], "xs": [0.5, 1.5], "ok": false});
+    let opt = CompressOptions::default().with_typed_arrays(true);
+    let (_, report) = compress_with_report(&v, &opt).unwrap();
+    // 定型布尔每个 1 位、f32 元素 32 位；独立布尔值只有 3 位标签
+    assert_eq!(report.bools.count, 5);
+    assert_eq!(report.bools.by_bits.get(&1), Some(&4));
+    assert_eq!(report.bools.by_bits.get(&3), Some(&1));
+    assert_eq!(report.floats.count, 2);
+    assert_eq!(report.floats.bits, 2 * 32);
+}
+
+#[test]
+fn json_size_is_measured_on_the_input_not_the_quantized_copy() {
+    let v = json!({"xs": [1.23456789, 2.34567891, 3.45678912]});
+    let opt = CompressOptions::default().with_float_precision(Some(FloatPrecision::Decimals(1)));
+    let (_, report) = compress_with_report(&v, &opt).unwrap();
+    assert_eq!(report.sizes.json_bytes, serde_json::to_vec(&v).unwrap().len());
+}
@@ -0,0 +1,50 @@
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_prefix, decompress_with_options, CompressOptions, DecodeOptions, Error};
+use serde_json::json;
+
+fn strict() -> DecodeOptions { DecodeOptions::default().with_strict(true) }
+
+#[test]
+fn strict_accepts_exact_payload() {
+    let v = json!({"a": [1, 2, 3], "b": "text"});
+    let bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    assert_eq!(decompress_with_options(&bytes, &strict()).unwrap(), v);
+}
+
+#[test]
+fn strict_rejects_trailing_bytes() {
+    let v = json!({"a": 1});
+    let mut bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    let len = bytes.len();
+    bytes.extend_from_slice(&[0, 0]);
+    // 宽松模式保持原行为
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    let err = decompress_with_options(&bytes, &strict()).unwrap_err();
+    assert!(matches!(err, Error::TrailingData { offset } if offset == len as u64));
+    assert_eq!(err.code(), "trailing_data");
+}
+
+#[test]
+fn strict_rejects_nonzero_padding_bits() {
+    let v = json!(true);
+    let mut bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    // 数据区仅 3 位，末字节高位为填充
+    *bytes.last_mut().unwrap() |= 0x80;
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    assert!(matches!(decompress_with_options(&bytes, &strict()), Err(Error::TrailingData { .. })));
+}
+
+#[test]
+fn prefix_decodes_back_to_back_payloads() {
+    let docs = [json!({"id": 1}), json!(["x", "y"]), json!(null)];
+    let mut buf = Vec::new();
+    for d in &docs { buf.extend(compress_to_bytes(d, &CompressOptions::default()).unwrap()); }
+
+    let mut rest = &buf[..];
+    let mut out = Vec::new();
+    while !rest.is_empty() {
+        let (v, used) = decompress_prefix(rest).unwrap();
+        out.push(v);
+        rest = &rest[used..];
+    }
+    assert_eq!(out, docs);
+}
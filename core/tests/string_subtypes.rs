@@ -0,0 +1,151 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, inspect, CompressOptions};
+use serde_json::{json, Value};
+
+fn subtypes() -> CompressOptions {
+    CompressOptions::default().with_string_subtypes(true)
+}
+
+/// 单个字符串按子类型写入后选中的写法；原文为 None
+fn form_of(s: &str) -> Option<String> {
+    let bytes = compress_to_bytes(&json!(s), &subtypes()).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), json!(s), "{s:?}");
+    let dump = disassemble(&bytes).to_string();
+    ["uuid", "hex", "base64", "decimal"].into_iter().find(|k| dump.contains(&format!("({k})"))).map(String::from)
+}
+
+#[test]
+fn canonical_forms_are_detected() {
+    assert_eq!(form_of("550e8400-e29b-41d4-a716-446655440000").as_deref(), Some("uuid"));
+    assert_eq!(form_of("550E8400-E29B-41D4-A716-446655440000").as_deref(), Some("uuid"));
+    assert_eq!(form_of("9f86d081884c7d659a2feaa0c55ad015").as_deref(), Some("hex"));
+    // 奇数位十六进制同样可逆
+    assert_eq!(form_of("abcdefabc").as_deref(), Some("hex"));
+    assert_eq!(form_of("aGVsbG8gd29ybGQ=").as_deref(), Some("base64"));
+    assert_eq!(form_of("0").as_deref(), Some("decimal"));
+    assert_eq!(form_of("18446744073709551615").as_deref(), Some("decimal"));
+}
+
+#[test]
+fn non_canonical_strings_fall_back_to_a_weaker_form() {
+    for (s, form) in [
+        // 大小写混用：不是 UUID，也不是十六进制
+        ("550e8400-E29B-41d4-a716-446655440000", None),
+        ("DeadBeefDeadBee", None),
+        // 前导零、超出 u64：不是十进制，但仍是十六进制
+        ("0042", Some("hex")),
+        ("18446744073709551616", Some("hex")),
+        // 带符号
+        ("+5", None),
+        ("-5", None),
+        // 缺少填充或非标准字母表的 Base64
+        ("aGVsbG8", None),
+        ("aGV-bG8_", None),
+        // 空串与多字节文本
+        ("", None),
+        ("hello, 世界", None),
+    ] {
+        assert_eq!(form_of(s).as_deref(), form, "{s:?}");
+    }
+}
+
+#[test]
+fn shortest_form_wins_between_overlapping_candidates() {
+    // 短数字串同时是十六进制与十进制：十进制只需 ULEB128
+    assert_eq!(form_of("12").as_deref(), Some("decimal"));
+    // "abcd" 同时是规范 Base64（3 字节），但十六进制每位 4 bit 更短
+    assert_eq!(form_of("abcd").as_deref(), Some("hex"));
+    // 含非十六进制字符时只剩 Base64
+    assert_eq!(form_of("bm90IGhleA==").as_deref(), Some("base64"));
+}
+
+#[test]
+fn pool_entries_stay_plain_and_references_skip_subtypes() {
+    let id = "550e8400-e29b-41d4-a716-446655440000";
+    let v = json!([id, id, id, {"other": "00000000-0000-4000-8000-000000000001"}]);
+    let bytes = compress_to_bytes(&v, &subtypes().with_value_pool(true)).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    // 池条目以原文存储；数据区只有未入池的那一个按子类型写入
+    assert_eq!(inspect(&bytes).unwrap().pool, vec![json!(id)]);
+    assert_eq!(disassemble(&bytes).to_string().matches("(uuid)").count(), 1);
+}
+
+#[test]
+fn projection_skips_every_subtype() {
+    let v = json!({
+        "a": "550e8400-e29b-41d4-a716-446655440000",
+        "b": "deadbeef0",
+        "c": "aGVsbG8gd29ybGQ=",
+        "d": "12345678901234",
+        "e": "plain text",
+        "z": "target",
+    });
+    let bytes = compress_to_bytes(&v, &subtypes()).unwrap();
+    assert_eq!(decompress_projected(&bytes, &["/z"]).unwrap(), json!({"z": "target"}));
+    assert_eq!(decompress_projected(&bytes, &["/d"]).unwrap(), json!({"d": "12345678901234"}));
+}
+
+/// 手工构造：根为单个带子类型标记的字符串；`payload` 写入标记之后的内容
+fn subtype_package(payload: impl FnOnce(&mut BitWriter)) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::STRING_SUBTYPES, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::STRING as u64, 3);
+    payload(&mut w);
+    w.into_bytes()
+}
+
+fn decode_code(bytes: &[u8]) -> &'static str {
+    let err = decompress_from_bytes(bytes).unwrap_err();
+    assert!(decompress_projected(bytes, &["/x"]).is_err());
+    err.code()
+}
+
+#[test]
+fn malformed_subtype_payloads_are_data_errors() {
+    // UUID 只有 4 字节
+    let truncated_uuid = subtype_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits(0, 2);
+        w.write_bits(0, 1);
+        for _ in 0..4 { w.write_byte(0xab); }
+    });
+    assert_eq!(decode_code(&truncated_uuid), "truncated_data");
+
+    // 十六进制声明了远超剩余数据的位数：不得按声明长度预分配
+    let huge_hex = subtype_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits(1, 2);
+        w.write_bits(0, 1);
+        write_uleb128(w, u64::MAX >> 4);
+    });
+    assert_eq!(decode_code(&huge_hex), "truncated_data");
+
+    let huge_base64 = subtype_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits(2, 2);
+        write_uleb128(w, u64::MAX);
+    });
+    assert_eq!(decode_code(&huge_base64), "truncated_data");
+
+    // 十进制载荷的变长整数超出 u64
+    let overlong_decimal = subtype_package(|w| {
+        w.write_bits(1, 1);
+        w.write_bits(3, 2);
+        for _ in 0..10 { w.write_byte(0xff); }
+        w.write_byte(0x01);
+    });
+    assert_eq!(decode_code(&overlong_decimal), "varint_error");
+}
+
+#[test]
+fn subtype_marks_require_the_header_flag() {
+    // 未启用时不写子类型标记，包头也不带该标志
+    let uuid: Value = json!("550e8400-e29b-41d4-a716-446655440000");
+    let with = compress_to_bytes(&uuid, &subtypes()).unwrap();
+    let without = compress_to_bytes(&uuid, &CompressOptions::default()).unwrap();
+    assert_ne!(inspect(&with).unwrap().flags & flags::STRING_SUBTYPES, 0);
+    assert_eq!(inspect(&without).unwrap().flags & flags::STRING_SUBTYPES, 0);
+    assert!(with.len() < without.len());
+}
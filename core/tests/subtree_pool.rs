@@ -0,0 +1,112 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, inspect, CompressOptions};
+use serde_json::{json, Value};
+
+fn subtrees() -> CompressOptions {
+    CompressOptions::default().with_subtree_pool(true)
+}
+
+fn pool_of(v: &Value, opt: &CompressOptions) -> (Vec<u8>, Vec<Value>) {
+    let bytes = compress_to_bytes(v, opt).unwrap();
+    assert_eq!(&decompress_from_bytes(&bytes).unwrap(), v);
+    let pool = inspect(&bytes).unwrap().pool;
+    (bytes, pool)
+}
+
+#[test]
+fn subtree_ids_follow_string_pool_in_document_order() {
+    let a = json!({"kind": 1, "tags": [7, 8, 9]});
+    let b = json!([4, 5, 6, 7, 8]);
+    let v = json!({"s": ["shared-string", "shared-string", "shared-string"], "z": [b, a, b, a, b, a]});
+    let (bytes, pool) = pool_of(&v, &subtrees().with_value_pool(true));
+    assert_eq!(pool, [json!("shared-string"), b, a]);
+    let dump = disassemble(&bytes).to_string();
+    assert!(dump.contains("object ref #2"));
+    assert!(dump.contains("array ref #1"));
+}
+
+#[test]
+fn only_exactly_equal_subtrees_share_an_entry() {
+    // 1 与 1.0、键相同但值类型不同的对象不合并
+    let int = json!({"v": 1, "w": [10, 20, 30]});
+    let float = json!({"v": 1.0, "w": [10, 20, 30]});
+    let v = json!([int, int, int, float]);
+    let (bytes, pool) = pool_of(&v, &subtrees());
+    assert_eq!(pool, [int]);
+    assert!(decompress_from_bytes(&bytes).unwrap()[3]["v"].is_f64());
+}
+
+#[test]
+fn projection_reaches_into_pooled_subtrees() {
+    let address = json!({"street": "1 Main St", "city": "Springfield", "geo": [1.5, 2.5]});
+    let v = json!({"users": [{"id": 1, "address": address}, {"id": 2, "address": address}, {"id": 3, "address": address}]});
+    let (bytes, pool) = pool_of(&v, &subtrees());
+    assert_eq!(pool, [address]);
+    assert_eq!(decompress_projected(&bytes, &["/users/1/address/geo/0"]).unwrap(), json!({"users": [{"address": {"geo": [1.5]}}]}));
+    assert_eq!(decompress_projected(&bytes, &["/users/*/id"]).unwrap(), json!({"users": [{"id": 1}, {"id": 2}, {"id": 3}]}));
+}
+
+#[test]
+fn small_or_unique_subtrees_keep_legacy_format() {
+    let v = json!({"a": {"x": 1}, "b": {"x": 1}, "c": [1, 2, 3, 4, 5]});
+    let opt = CompressOptions::default().with_subtree_pool(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(bytes, compress_to_bytes(&v, &CompressOptions::default()).unwrap());
+}
+
+#[test]
+fn subtrees_without_net_savings_stay_inline() {
+    // 空数组内联仅 13 位，引用本身就要 12 位，加上池条目后入池更大
+    let v = json!([[], [], [], []]);
+    let opt = CompressOptions::default().with_subtree_pool(true).with_pool_min_subtree_nodes(1);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(bytes, compress_to_bytes(&v, &CompressOptions::default()).unwrap());
+}
+
+#[test]
+fn occurrences_inside_pooled_ancestor_are_not_counted() {
+    let inner = json!({"tags": ["alpha", "beta", "gamma"], "level": 3});
+    let outer = json!({"inner": inner, "owner": "someone@example.com"});
+    // inner 共出现 3 次，但其中 2 次位于入池的 outer 之内，仅剩 1 处可引用
+    let v = json!([outer, outer, inner]);
+    let opt = CompressOptions::default().with_subtree_pool(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert_eq!(decompress_from_bytes(&bytes).unwrap(), v);
+    let info = inspect(&bytes).unwrap();
+    assert_eq!(info.pool, vec![outer]);
+}
+
+/// 手工构造：值池为 `entries` 个空数组条目，根值为对象池引用 `id`
+fn subtree_package(fl: u64, pool_len: u64, entries: u64, id: u64) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: fl, ..PackageHeader::new(0, pool_len) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    for _ in 0..entries {
+        w.write_bits(tag::ARRAY as u64, 3);
+        write_uleb128(&mut w, 0);
+    }
+    w.write_bits(tag::OBJECT as u64, 3);
+    w.write_bits(1, 1);
+    write_uleb128(&mut w, id);
+    w.into_bytes()
+}
+
+#[test]
+fn malformed_subtree_references_are_data_errors() {
+    let pooled = flags::VALUE_POOL | flags::SUBTREE_POOL;
+    assert_eq!(decompress_from_bytes(&subtree_package(pooled, 2, 2, 1)).unwrap(), json!([]));
+
+    for (bytes, code) in [
+        // 引用 ID 超出条目数
+        (subtree_package(pooled, 2, 2, 2), "pool_id_out_of_range"),
+        (subtree_package(pooled, 1, 1, u64::MAX), "pool_id_out_of_range"),
+        // 带容器引用位但未启用值池区
+        (subtree_package(flags::SUBTREE_POOL, 0, 0, 0), "pool_missing"),
+        // 包头声明的条目数远超剩余数据
+        (subtree_package(pooled, u64::MAX >> 1, 1, 0), "truncated_data"),
+    ] {
+        assert_eq!(decompress_from_bytes(&bytes).unwrap_err().code(), code);
+        assert_eq!(decompress_projected(&bytes, &[""]).unwrap_err().code(), code);
+    }
+}
@@ -0,0 +1,141 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_sleb128, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, CompressOptions, PackWriter};
+use serde_json::{json, Value};
+
+fn timestamps() -> CompressOptions {
+    CompressOptions::default().with_timestamp_strings(true)
+}
+
+fn roundtrip(v: &Value, opt: &CompressOptions) -> Vec<u8> {
+    let bytes = compress_to_bytes(v, opt).unwrap();
+    assert_eq!(&decompress_from_bytes(&bytes).unwrap(), v);
+    bytes
+}
+
+fn is_timestamp(s: &str) -> bool {
+    let bytes = roundtrip(&json!(s), &timestamps());
+    disassemble(&bytes).to_string().contains("(timestamp)")
+}
+
+#[test]
+fn only_exactly_rebuildable_strings_are_timestamps() {
+    for s in [
+        "2024-03-01T12:00:00Z",
+        "2000-02-29T00:00:00Z",
+        "0000-01-01T00:00:00Z",
+        "9999-12-31T23:59:59.999999999+23:59",
+        // 偏移 -00:00 与 Z、+00:00 区分保存；末尾的 0 保留
+        "2024-01-01T00:00:00-00:00",
+        "2024-01-01T00:00:00+00:00",
+        "2024-01-01T00:00:00.100Z",
+    ] {
+        assert!(is_timestamp(s), "{s}");
+    }
+    for s in [
+        "2024-03-01t12:00:00z",
+        "2016-12-31T23:59:60Z",
+        "1900-02-29T00:00:00Z",
+        "2023-04-31T00:00:00Z",
+        "2024-03-01T24:00:00Z",
+        "2024-03-01T12:00Z",
+        "2024-03-01T12:00:00",
+        "2024-03-01T12:00:00.Z",
+        "2024-03-01T12:00:00.1234567890Z",
+        "2024-03-01T12:00:00+24:00",
+        "2024-03-01T12:00:00+0100",
+        "+2024-03-01T12:00:00Z",
+    ] {
+        assert!(!is_timestamp(s), "{s}");
+    }
+}
+
+#[test]
+fn negative_and_extreme_deltas_roundtrip() {
+    // 递减序列、跨越 1970 年前后、两端极值首尾相接
+    let v = json!([
+        "2024-03-01T00:00:00Z",
+        "2024-02-29T23:59:59Z",
+        "1969-12-31T23:59:59Z",
+        "9999-12-31T23:59:59Z",
+        "0000-01-01T00:00:00Z",
+        "9999-12-31T23:59:59Z",
+    ]);
+    let bytes = roundtrip(&v, &timestamps());
+    assert_eq!(disassemble(&bytes).to_string().matches("(timestamp)").count(), 6);
+}
+
+#[test]
+fn delta_state_skips_pool_references_and_projection() {
+    // 池条目与池引用不带标记，不推进差分状态；跳读时仍须读出时间戳维护状态
+    let shared = "2024-05-01T08:00:00Z";
+    let v = json!({
+        "a": "2024-05-03T08:00:00Z",
+        "b": [shared, shared, shared],
+        "c": "2024-05-02T08:00:00Z",
+        "d": "2024-05-04T08:00:00Z",
+    });
+    let opt = timestamps().with_value_pool(true);
+    let bytes = roundtrip(&v, &opt);
+    assert_eq!(decompress_projected(&bytes, &["/d"]).unwrap(), json!({"d": "2024-05-04T08:00:00Z"}));
+    assert_eq!(decompress_projected(&bytes, &["/c", "/b/1"]).unwrap(), json!({"b": [shared], "c": "2024-05-02T08:00:00Z"}));
+
+    let mut w = PackWriter::new(opt);
+    w.value(&v).unwrap();
+    assert_eq!(w.finish().unwrap(), bytes);
+}
+
+/// (秒数差分, 小数位数, 小数, 偏移分钟数)
+type Item = (i64, u64, u64, Option<u64>);
+
+/// 手工构造：根为字符串数组，每项为带时间戳标记的时间戳载荷
+fn timestamp_package(items: &[Item]) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::TIMESTAMPS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::ARRAY as u64, 3);
+    write_uleb128(&mut w, items.len() as u64);
+    for &(delta, digits, frac, offset) in items {
+        w.write_bits(tag::STRING as u64, 3);
+        w.write_bits(1, 1);
+        write_sleb128(&mut w, delta);
+        w.write_bits(digits, 4);
+        if digits > 0 { write_uleb128(&mut w, frac); }
+        match offset {
+            None => w.write_bits(0, 1),
+            Some(minutes) => {
+                w.write_bits(1, 1);
+                w.write_bits(0, 1);
+                write_uleb128(&mut w, minutes);
+            }
+        }
+    }
+    w.into_bytes()
+}
+
+#[test]
+fn malformed_timestamp_payloads_are_data_errors() {
+    // 9999-12-31T23:59:59Z 与 0000-01-01T00:00:00Z 的秒数
+    let (max, min) = (253_402_300_799, -62_167_219_200);
+    assert_eq!(decompress_from_bytes(&timestamp_package(&[(max, 0, 0, None), (min - max, 0, 0, None)])).unwrap(), json!(["9999-12-31T23:59:59Z", "0000-01-01T00:00:00Z"]));
+
+    let cases: [&[Item]; 7] = [
+        // 差分累加溢出 i64（正向、负向）
+        &[(max, 0, 0, None), (i64::MAX, 0, 0, None)],
+        &[(min, 0, 0, None), (i64::MIN, 0, 0, None)],
+        // 超出 0000–9999 年
+        &[(max + 1, 0, 0, None)],
+        &[(min - 1, 0, 0, None)],
+        // 小数位数超过 9、小数值超出位数、偏移满 24 小时
+        &[(0, 10, 0, None)],
+        &[(0, 3, 1000, None)],
+        &[(0, 0, 0, Some(24 * 60))],
+    ];
+    for items in cases {
+        let bytes = timestamp_package(items);
+        let err = decompress_from_bytes(&bytes).unwrap_err();
+        assert_eq!(err.code(), "invalid_timestamp", "{items:?}");
+        assert_eq!(decompress_projected(&bytes, &["/9"]).unwrap_err().code(), "invalid_timestamp", "{items:?}");
+        assert!(disassemble(&bytes).error.is_some());
+    }
+}
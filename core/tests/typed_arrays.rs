@@ -0,0 +1,97 @@
+use json_packer::test_expose::{flags, tag, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, CompressOptions, Error, PackWriter};
+use serde_json::{json, Value};
+
+fn typed() -> CompressOptions {
+    CompressOptions::default().with_typed_arrays(true)
+}
+
+/// 数组按定型写入时的元素类型；逐元素编码为 None
+fn kind_of(v: &Value) -> Option<String> {
+    let bytes = compress_to_bytes(v, &typed()).unwrap();
+    assert_eq!(&decompress_from_bytes(&bytes).unwrap(), v);
+    let dump = disassemble(&bytes).to_string();
+    ["f64", "f32", "bool"].into_iter().find(|k| dump.contains(&format!("({k})"))).map(String::from)
+}
+
+#[test]
+fn element_kind_follows_exactness_of_every_element() {
+    assert_eq!(kind_of(&json!([0.5, -2.25, 1024.0])).as_deref(), Some("f32"));
+    // 单个无法无损转为 f32 的元素使整个数组写为 f64
+    assert_eq!(kind_of(&json!([0.5, 0.1])).as_deref(), Some("f64"));
+    assert_eq!(kind_of(&json!([f32::MAX as f64, f32::MIN_POSITIVE as f64])).as_deref(), Some("f32"));
+    assert_eq!(kind_of(&json!([1e300, 5e-324])).as_deref(), Some("f64"));
+    assert_eq!(kind_of(&json!([true])).as_deref(), Some("bool"));
+    // 整数、null、字符串、嵌套容器、浮点与布尔混合、空数组均逐元素编码
+    for v in [json!([1, 2]), json!([1.5, 2]), json!([1.5, null]), json!([true, 1.5]), json!([[1, 2]]), json!(["x"]), json!([])] {
+        assert_eq!(kind_of(&v), None, "{v}");
+    }
+}
+
+#[test]
+fn negative_zero_keeps_its_sign() {
+    let bytes = compress_to_bytes(&json!([-0.0, 0.0]), &typed()).unwrap();
+    let out = decompress_from_bytes(&bytes).unwrap();
+    assert!(out[0].as_f64().unwrap().is_sign_negative());
+    assert!(out[1].as_f64().unwrap().is_sign_positive());
+}
+
+#[test]
+fn projection_skips_blocks_and_pack_writer_matches() {
+    let v = json!({"a": [0.1, 0.2, 0.3], "b": [true, false, true], "c": [0.5, 1.5], "z": "end"});
+    let bytes = compress_to_bytes(&v, &typed()).unwrap();
+    assert_eq!(decompress_projected(&bytes, &["/z"]).unwrap(), json!({"z": "end"}));
+    assert_eq!(decompress_projected(&bytes, &["/a/2", "/b/1", "/c/0"]).unwrap(), json!({"a": [0.3], "b": [false], "c": [0.5]}));
+
+    let mut w = PackWriter::new(typed());
+    w.value(&v).unwrap();
+    assert_eq!(w.finish().unwrap(), bytes);
+}
+
+/// 手工构造：根为带类型标记的数组
+fn typed_package(kind: u64, len: u64, body: impl FnOnce(&mut BitWriter)) -> Vec<u8> {
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::TYPED_ARRAYS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_uleb128(&mut w, 0);
+    w.write_bits(tag::ARRAY as u64, 3);
+    w.write_bits(1, 1);
+    w.write_bits(kind, 2);
+    write_uleb128(&mut w, len);
+    body(&mut w);
+    w.into_bytes()
+}
+
+#[test]
+fn reserved_kind_is_rejected_as_data_error() {
+    let bytes = typed_package(3, 1, |w| w.write_bits(0, 1));
+    let err = decompress_from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err.kind(), Error::InvalidTypedArray(3)));
+    assert_eq!(err.code(), "invalid_typed_array");
+    assert!(decompress_projected(&bytes, &["/0"]).is_err());
+    assert!(disassemble(&bytes).error.is_some());
+}
+
+#[test]
+fn oversized_lengths_are_truncation_errors() {
+    // 声明长度远超剩余数据：不得按声明长度预分配；跳读时位数乘法溢出同样报错
+    for (kind, len) in [(0, 3), (1, u64::MAX >> 8), (0, u64::MAX / 32), (2, u64::MAX)] {
+        let bytes = typed_package(kind, len, |w| w.write_bits(0, 64));
+        assert_eq!(decompress_from_bytes(&bytes).unwrap_err().code(), "truncated_data", "{kind} x {len}");
+        assert!(decompress_projected(&bytes, &["/x"]).is_err());
+        assert!(decompress_projected(&bytes, &["/0"]).is_err());
+    }
+}
+
+#[test]
+fn non_finite_elements_need_the_header_flag() {
+    let bytes = typed_package(0, 2, |w| {
+        w.write_bits(1.5f64.to_bits(), 64);
+        w.write_bits(f64::NAN.to_bits(), 64);
+    });
+    assert_eq!(decompress_from_bytes(&bytes).unwrap_err().code(), "illegal_float");
+    // 定型块整体读出：投影只取前一个元素也会校验整个块
+    for path in ["/0", "/1"] {
+        assert_eq!(decompress_projected(&bytes, &[path]).unwrap_err().code(), "illegal_float", "{path}");
+    }
+}
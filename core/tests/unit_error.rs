@@ -9,4 +9,18 @@ fn error_variants_debug() {
     let _ = Error::BadMagic;
     let _ = Error::BadVersion;
     let _ = Error::HuffmanError;
+    let _ = Error::InvalidTag(0);
+    let _ = Error::TruncatedData;
+}
+
+#[test]
+fn error_codes_are_stable_and_see_through_location() {
+    assert_eq!(Error::BadMagic.code(), "bad_magic");
+    assert_eq!(Error::InvalidTag(7).code(), "invalid_tag");
+    let located = Error::Located { offset: 42, path: "/a/0".to_string(), source: Box::new(Error::VarintError) };
+    assert_eq!(located.code(), "varint_error");
+    assert!(matches!(located.kind(), Error::VarintError));
+    assert_eq!(located.bit_offset(), Some(42));
+    assert_eq!(located.path(), Some("/a/0"));
+    assert_eq!(located.to_string(), "varint overflow or truncated (at bit 42, path /a/0)");
 }
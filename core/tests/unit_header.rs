@@ -17,3 +17,19 @@ fn header_magic_version_constants() {
     assert_eq!(&MAGIC, b"JCPR");
     assert_eq!(VERSION_V1, 0x01);
 }
+
+#[test]
+fn header_v3_flags_and_metadata_roundtrip() {
+    use json_packer::test_expose::{flags, write_package_header, PackageHeader, VERSION_V3};
+    let mut hdr = PackageHeader::new(3, 1);
+    hdr.version = VERSION_V3;
+    hdr.flags = flags::VALUE_POOL | flags::METADATA;
+    hdr.metadata.insert("k".to_string(), "v".to_string());
+    let mut w = BitWriter::new();
+    write_package_header(&mut w, &hdr);
+    let bytes = w.into_bytes();
+    let mut r = BitReader::new(&bytes);
+    let h = read_header(&mut r).unwrap();
+    assert_eq!(h, hdr);
+    assert!(h.has_pool());
+}
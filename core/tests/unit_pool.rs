@@ -27,3 +27,20 @@ fn write_pool_and_header_v2() {
     assert_eq!(hdr.dict_len, 0);
     assert_eq!(hdr.pool_len, 2);
 }
+
+#[test]
+fn cost_model_selects_profitable_strings() {
+    let mut items = vec![json!("abcd"); 500];
+    items.extend([json!("x"), json!("x"), json!("rarer_str"), json!("rarer_str"), json!("rarer_str")]);
+    let pool = collect_string_pool(&json!(items), PoolConfig::default());
+    // 短字符串高频重复也能入池
+    assert_eq!(pool.index.get("abcd"), Some(&0));
+    assert_eq!(pool.index.get("rarer_str"), Some(&1));
+    // 单字节字符串重复两次：内联 2×16 位，入池 16 位条目 + 2×8 位引用，无收益
+    assert!(!pool.index.contains_key("x"));
+
+    // 可选阈值作为额外下限
+    let cfg = PoolConfig { min_repeats: 0, min_string_len: 5 };
+    let pool = collect_string_pool(&json!(items), cfg);
+    assert_eq!(pool.entries, vec!["rarer_str".to_string()]);
+}
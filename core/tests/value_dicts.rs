@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use json_packer::test_expose::{flags, tag, write_dictionary, write_package_header, write_uleb128, BitWriter, PackageHeader, VERSION_V3};
+use json_packer::{compress_to_bytes, decompress_from_bytes, decompress_projected, disassemble, inspect, CompressOptions, Compressor, DecodeOptions, Decompressor, HuffmanCodec, PackWriter, SharedDictionary};
+use serde_json::{json, Value};
+
+fn dicts() -> CompressOptions {
+    CompressOptions::default().with_value_dicts(true)
+}
+
+/// 按值字典写入的字符串个数
+fn dict_hits(v: &Value, opt: &CompressOptions) -> usize {
+    let bytes = compress_to_bytes(v, opt).unwrap();
+    assert_eq!(&decompress_from_bytes(&bytes).unwrap(), v);
+    let mut w = PackWriter::new(opt.clone());
+    w.value(v).unwrap();
+    assert_eq!(w.finish().unwrap(), bytes);
+    disassemble(&bytes).to_string().matches("(value dict)").count()
+}
+
+fn rows(n: usize, row: impl Fn(usize) -> Value) -> Value {
+    Value::Array((0..n).map(row).collect())
+}
+
+#[test]
+fn tables_pay_off_only_for_repeated_values() {
+    // 单一取值的表码长为 1
+    assert_eq!(dict_hits(&rows(20, |_| json!({"s": "active"})), &dicts()), 20);
+    assert_eq!(dict_hits(&rows(20, |i| json!({"s": (["a", "b", "c"][i % 3])})), &dicts()), 20);
+    // 取值各不相同、只出现一次：建表不划算
+    assert_eq!(dict_hits(&rows(20, |i| json!({"s": format!("user-{i}")})), &dicts()), 0);
+    assert_eq!(dict_hits(&json!({"s": "active"}), &dicts()), 0);
+}
+
+#[test]
+fn only_direct_string_members_use_the_table() {
+    // 同名键在任意深度共用一张表；非字符串值、数组中的字符串照常写入
+    let v = json!([
+        {"s": "on"}, {"s": "on"}, {"s": "off"}, {"s": "on"},
+        {"nested": {"s": "off"}},
+        {"s": null}, {"s": 1}, {"s": ["on", "on"]}, {"s": {"s": "on"}},
+    ]);
+    assert_eq!(dict_hits(&v, &dicts()), 6);
+}
+
+#[test]
+fn table_strings_bypass_pools_and_projection_skips_them() {
+    let long = "a-fairly-long-status-value";
+    let v = json!({"rows": rows(12, |i| json!({"s": ([long, "short"][i % 2])})), "other": [long, long, long], "z": 1});
+    let opt = dicts().with_value_pool(true).with_huffman_pool_refs(true);
+    // 同一字符串在表键下走值字典，在其他位置仍可入池引用
+    assert_eq!(dict_hits(&v, &opt), 12);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+    assert!(inspect(&bytes).unwrap().pool.contains(&json!(long)));
+    assert_eq!(decompress_projected(&bytes, &["/z"]).unwrap(), json!({"z": 1}));
+    assert_eq!(decompress_projected(&bytes, &["/rows/3/s"]).unwrap(), json!({"rows": [{"s": "short"}]}));
+}
+
+#[test]
+fn value_dicts_with_shared_dictionary() {
+    let v = rows(20, |i| json!({"status": (["active", "inactive", "pending"][i % 3])}));
+    let dict = SharedDictionary::from_samples([&v], None).unwrap();
+    let mut c = Compressor::with_dictionary(dicts(), dict.clone());
+    let d = Decompressor::with_dictionary(DecodeOptions::default().with_strict(true), dict);
+    assert_eq!(d.decompress(&c.compress(&v).unwrap()).unwrap(), v);
+}
+
+/// 手工构造：键字典 {"s", "t"}，值字典区由 `tables` 写入，数据区由 `body` 写入
+fn value_dict_package(tables: impl FnOnce(&mut BitWriter, &HuffmanCodec), body: impl FnOnce(&mut BitWriter, &HuffmanCodec)) -> Vec<u8> {
+    let keys = HashMap::from([("s", 2u64), ("t", 1)]);
+    let codec = HuffmanCodec::from_frequencies(&keys).unwrap();
+    let mut w = BitWriter::new();
+    let hdr = PackageHeader { version: VERSION_V3, flags: flags::VALUE_DICTS, ..PackageHeader::new(0, 0) };
+    write_package_header(&mut w, &hdr);
+    write_dictionary(&mut w, &keys);
+    tables(&mut w, &codec);
+    body(&mut w, &codec);
+    w.into_bytes()
+}
+
+/// 数据区：{"s": <字符串>}，字符串部分由 `value` 写入
+fn object_s(value: impl FnOnce(&mut BitWriter)) -> impl FnOnce(&mut BitWriter, &HuffmanCodec) {
+    move |w, codec| {
+        w.write_bits(tag::OBJECT as u64, 3);
+        write_uleb128(w, 1);
+        codec.write_key_code("s", w).unwrap();
+        w.write_bits(tag::STRING as u64, 3);
+        value(w);
+    }
+}
+
+#[test]
+fn malformed_value_dicts_are_data_errors() {
+    let table = |w: &mut BitWriter, codec: &HuffmanCodec| {
+        write_uleb128(w, 1);
+        codec.write_key_code("s", w).unwrap();
+        write_dictionary(w, &HashMap::from([("on", 1u64), ("off", 1)]));
+    };
+    let valid = value_dict_package(table, object_s(|w| w.write_bits(0, 1)));
+    assert_eq!(decompress_from_bytes(&valid).unwrap(), json!({"s": "off"}));
+
+    // 空表
+    let empty = value_dict_package(|w, codec| {
+        write_uleb128(w, 1);
+        codec.write_key_code("s", w).unwrap();
+        write_uleb128(w, 0);
+    }, object_s(|_| {}));
+    assert_eq!(decompress_from_bytes(&empty).unwrap_err().code(), "huffman_error");
+
+    // 声明的表数远超剩余数据
+    let huge_count = value_dict_package(|w, _| write_uleb128(w, u64::MAX), object_s(|_| {}));
+    assert_eq!(decompress_from_bytes(&huge_count).unwrap_err().code(), "truncated_data");
+
+    // 表项长度超出剩余数据
+    let huge_entry = value_dict_package(|w, codec| {
+        write_uleb128(w, 1);
+        codec.write_key_code("t", w).unwrap();
+        write_uleb128(w, 1);
+        write_uleb128(w, u64::MAX >> 1);
+    }, object_s(|_| {}));
+    assert_eq!(decompress_from_bytes(&huge_entry).unwrap_err().code(), "truncated_data");
+
+    for bytes in [&empty, &huge_count, &huge_entry] {
+        assert!(decompress_from_bytes(bytes).is_err());
+        assert!(decompress_projected(bytes, &["/x"]).is_err());
+        assert!(disassemble(bytes).error.is_some());
+    }
+}
@@ -0,0 +1,65 @@
+use json_packer::{compress_to_bytes, decode_with_visitor, CompressOptions, Visitor};
+use serde_json::json;
+
+/// 记录事件的文本形式
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl Visitor for Events {
+    fn start_object(&mut self, len: usize) { self.0.push(format!("{{{len}")); }
+    fn key(&mut self, key: &str) { self.0.push(format!("k:{key}")); }
+    fn end_object(&mut self) { self.0.push("}".into()); }
+    fn start_array(&mut self, len: usize) { self.0.push(format!("[{len}")); }
+    fn end_array(&mut self) { self.0.push("]".into()); }
+    fn value_null(&mut self) { self.0.push("null".into()); }
+    fn value_bool(&mut self, value: bool) { self.0.push(value.to_string()); }
+    fn value_int(&mut self, value: i64) { self.0.push(format!("i:{value}")); }
+    fn value_uint(&mut self, value: u64) { self.0.push(format!("u:{value}")); }
+    fn value_float(&mut self, value: f64) { self.0.push(format!("f:{value}")); }
+    fn value_str(&mut self, value: &str) { self.0.push(format!("s:{value}")); }
+}
+
+#[test]
+fn visitor_sees_events_in_document_order() {
+    let v = json!({"a": [1, u64::MAX, 1.5], "b": null, "c": {"d": "x", "e": true}});
+    let bytes = compress_to_bytes(&v, &CompressOptions::default()).unwrap();
+    let mut events = Events::default();
+    decode_with_visitor(&bytes, &mut events).unwrap();
+    assert_eq!(
+        events.0.join(" "),
+        format!("{{3 k:a [3 i:1 u:{} f:1.5 ] k:b null k:c {{2 k:d s:x k:e true }} }}", u64::MAX)
+    );
+}
+
+#[test]
+fn pooled_values_are_replayed() {
+    let addr = json!({"street": "1 Main St", "city": "Springfield"});
+    let v = json!([
+        {"status": "connected", "addr": addr},
+        {"status": "connected", "addr": addr},
+        {"status": "connected", "addr": addr},
+    ]);
+    let opt = CompressOptions::default()
+        .with_value_pool(true)
+        .with_subtree_pool(true)
+        .with_pool_min_subtree_nodes(1)
+        .with_huffman_pool_refs(true);
+    let bytes = compress_to_bytes(&v, &opt).unwrap();
+
+    // 只统计记录数与字符串值，不构建 Value
+    #[derive(Default)]
+    struct Count { records: usize, strings: usize, depth: usize }
+    impl Visitor for Count {
+        fn start_object(&mut self, _: usize) {
+            if self.depth == 1 { self.records += 1; }
+            self.depth += 1;
+        }
+        fn end_object(&mut self) { self.depth -= 1; }
+        fn start_array(&mut self, _: usize) { self.depth += 1; }
+        fn end_array(&mut self) { self.depth -= 1; }
+        fn value_str(&mut self, _: &str) { self.strings += 1; }
+    }
+    let mut count = Count::default();
+    decode_with_visitor(&bytes, &mut count).unwrap();
+    assert_eq!((count.records, count.strings, count.depth), (3, 9, 0));
+}
@@ -8,9 +8,15 @@ fn to_core_opts(opts: &Options) -> CompressOptions {
     enable_value_pool: opts.enable_value_pool.unwrap_or(false),
     pool_min_repeats: opts.pool_min_repeats.unwrap_or(3),
     pool_min_string_len: opts.pool_min_string_len.unwrap_or(8) as usize,
+    ..Default::default()
   }
 }
 
+/// 错误信息带稳定错误码（如 `[truncated_data]`），便于 JS 侧按码分支
+fn core_error(op: &str, e: core::Error) -> Error {
+  Error::new(Status::GenericFailure, format!("{op} error [{}]: {e}", e.code()))
+}
+
 #[napi(object)]
 pub struct Options {
   pub enable_value_pool: Option<bool>,
@@ -23,7 +29,7 @@ pub fn compress_to_bytes(json_str: String, opts: Options) -> Result<Buffer> {
   let value: serde_json::Value = serde_json::from_str(&json_str)
     .map_err(|e| Error::new(Status::InvalidArg, format!("invalid JSON: {e}")))?;
   let bytes = core::compress_to_bytes(&value, &to_core_opts(&opts))
-    .map_err(|e| Error::new(Status::GenericFailure, format!("compress error: {e}")))?;
+    .map_err(|e| core_error("compress", e))?;
   Ok(Buffer::from(bytes))
 }
 
@@ -32,13 +38,13 @@ pub fn compress_to_base64(json_str: String, opts: Options) -> Result<String> {
   let value: serde_json::Value = serde_json::from_str(&json_str)
     .map_err(|e| Error::new(Status::InvalidArg, format!("invalid JSON: {e}")))?;
   core::compress_to_base64(&value, &to_core_opts(&opts))
-    .map_err(|e| Error::new(Status::GenericFailure, format!("compress error: {e}")))
+    .map_err(|e| core_error("compress", e))
 }
 
 #[napi]
 pub fn decompress_from_bytes(bytes: Buffer) -> Result<String> {
   let value = core::decompress_from_bytes(&bytes)
-    .map_err(|e| Error::new(Status::GenericFailure, format!("decompress error: {e}")))?;
+    .map_err(|e| core_error("decompress", e))?;
   serde_json::to_string(&value)
     .map_err(|e| Error::new(Status::GenericFailure, format!("stringify error: {e}")))
 }
@@ -46,7 +52,7 @@ pub fn decompress_from_bytes(bytes: Buffer) -> Result<String> {
 #[napi]
 pub fn decompress_from_base64(b64: String) -> Result<String> {
   let value = core::decompress_from_base64(&b64)
-    .map_err(|e| Error::new(Status::GenericFailure, format!("decompress error: {e}")))?;
+    .map_err(|e| core_error("decompress", e))?;
   serde_json::to_string(&value)
     .map_err(|e| Error::new(Status::GenericFailure, format!("stringify error: {e}")))
 }
@@ -1,5 +1,5 @@
 const { compressToBase64, decompressFromBase64 } = require('./index.js');
-const { deepEqual } = require('assert');
+const { deepEqual, throws } = require('assert');
 
 // 测试数据
 const testData = {
@@ -23,6 +23,9 @@ try {
   // 验证数据一致性
   const parsedDecompressed = JSON.parse(decompressed);
   deepEqual(parsedDecompressed, testData);
+
+  // 截断的压缩包：错误信息带稳定错误码
+  throws(() => decompressFromBase64(compressed.slice(0, compressed.length - 8)), /^Error: decompress error \[[a-z_]+\]/);
   
   console.log('✅ NAPI bindings test passed!');
   console.log(`Original size: ${jsonString.length} bytes`);